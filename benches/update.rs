@@ -0,0 +1,25 @@
+//! Benchmarks for the generation update across typical grid sizes.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use game_of_life_rs::{EdgeMode, World};
+
+fn bench_update(c: &mut Criterion) {
+    for (width, height) in [(160, 120), (640, 480), (1920, 1080)] {
+        c.bench_function(&format!("update {width}x{height}"), |b| {
+            // A long-lived board settles into still lifes and the tile
+            // skipping turns updates into no-ops, so give every sample a
+            // freshly seeded world.
+            b.iter_batched(
+                || {
+                    let mut rng = fastrand::Rng::with_seed(42);
+                    World::new(width, height, 0.3, EdgeMode::Dead, &mut rng)
+                },
+                |mut world| world.update(),
+                BatchSize::LargeInput,
+            );
+        });
+    }
+}
+
+criterion_group!(benches, bench_update);
+criterion_main!(benches);
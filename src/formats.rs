@@ -0,0 +1,600 @@
+//! Readers and writers for standard Game of Life file formats.
+
+use crate::{Rule, RuleParseError, World, MAX_CELLS};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// Errors produced while parsing an RLE pattern file.
+#[derive(Debug)]
+pub enum RleError {
+    Io(io::Error),
+    /// The `x = .., y = ..` header line is missing.
+    MissingHeader,
+    /// The header line could not be parsed.
+    InvalidHeader(String),
+    /// The pattern body contains a character outside the RLE alphabet.
+    UnexpectedChar(char),
+    /// The body's runs reach past the dimensions declared in the header.
+    RunPastHeader,
+    /// The header's `rule = ...` field could not be parsed.
+    InvalidRule(RuleParseError),
+}
+
+impl fmt::Display for RleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RleError::Io(err) => write!(f, "{err}"),
+            RleError::MissingHeader => write!(f, "missing `x = .., y = ..` header line"),
+            RleError::InvalidHeader(header) => write!(f, "invalid header line {header:?}"),
+            RleError::UnexpectedChar(c) => write!(f, "unexpected character {c:?} in pattern body"),
+            RleError::RunPastHeader => write!(f, "pattern body runs past the header dimensions"),
+            RleError::InvalidRule(err) => write!(f, "invalid rule in header: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RleError::Io(err) => Some(err),
+            RleError::InvalidRule(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RleError {
+    fn from(err: io::Error) -> Self {
+        RleError::Io(err)
+    }
+}
+
+/// An RLE pattern decoded into its header fields and live-cell
+/// coordinates relative to its top-left corner.
+struct RlePattern {
+    width: u32,
+    height: u32,
+    rule: Option<Rule>,
+    cells: Vec<(i32, i32)>,
+}
+
+/// A plaintext `.cells` pattern decoded into its bounding-box dimensions
+/// and live-cell coordinates relative to its top-left corner.
+struct CellsPattern {
+    width: u32,
+    height: u32,
+    cells: Vec<(i32, i32)>,
+}
+
+/// Reads a `#Life 1.06` pattern as live-cell coordinates, relative to
+/// the origin the way the format defines them. Pair the result with
+/// [`World::stamp`] to place it anywhere on a board.
+pub fn load_life106_pattern(reader: impl BufRead) -> io::Result<Vec<(i32, i32)>> {
+    let mut cells = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some(x), Some(y)) => cells.push((
+                x.parse::<i32>().map_err(invalid_data)?,
+                y.parse::<i32>().map_err(invalid_data)?,
+            )),
+            _ => {
+                return Err(invalid_data(format!(
+                    "expected `x y` coordinate pair, got {line:?}"
+                )))
+            }
+        }
+    }
+    Ok(cells)
+}
+
+/// Reads an RLE pattern as live-cell coordinates relative to its
+/// top-left corner, ignoring any rule in the header.
+pub fn load_rle_pattern(reader: impl BufRead) -> Result<Vec<(i32, i32)>, RleError> {
+    parse_rle(reader).map(|pattern| pattern.cells)
+}
+
+/// Live-cell coordinates paired with the rule their header declared,
+/// if any.
+pub type PatternWithRule = (Vec<(i32, i32)>, Option<Rule>);
+
+/// Like [`load_rle_pattern`], but also returns the rule declared in the
+/// header, if any, so callers stamping the pattern can honor it.
+pub fn load_rle_pattern_with_rule(reader: impl BufRead) -> Result<PatternWithRule, RleError> {
+    parse_rle(reader).map(|pattern| (pattern.cells, pattern.rule))
+}
+
+/// Reads a plaintext `.cells` pattern as live-cell coordinates relative
+/// to its top-left corner.
+pub fn load_cells_pattern(reader: impl BufRead) -> io::Result<Vec<(i32, i32)>> {
+    parse_cells(reader).map(|pattern| pattern.cells)
+}
+
+impl World {
+    /// Builds a world of the given dimensions from a `#Life 1.06` pattern:
+    /// one `x y` coordinate pair per line, centered at the origin. The
+    /// pattern is placed at the center of the grid and coordinates falling
+    /// outside it are clipped.
+    pub fn load_life106(reader: impl BufRead, width: u32, height: u32) -> io::Result<World> {
+        let cells = load_life106_pattern(reader)?;
+        let mut world = World::from_cells(width, height, &vec![false; (width * height) as usize]);
+        world.stamp(&cells, (width / 2) as i32, (height / 2) as i32);
+        Ok(world)
+    }
+
+    /// Builds a world from an RLE pattern, sized to the pattern's header.
+    pub fn load_rle(reader: impl BufRead) -> Result<World, RleError> {
+        Self::load_rle_impl(reader, None)
+    }
+
+    /// Builds a world of the given dimensions from an RLE pattern, placing
+    /// the pattern at the center of the grid. Cells falling outside the grid
+    /// are clipped.
+    pub fn load_rle_sized(
+        reader: impl BufRead,
+        width: u32,
+        height: u32,
+    ) -> Result<World, RleError> {
+        Self::load_rle_impl(reader, Some((width, height)))
+    }
+
+    fn load_rle_impl(reader: impl BufRead, size: Option<(u32, u32)>) -> Result<World, RleError> {
+        let pattern = parse_rle(reader)?;
+        let (width, height) = size.unwrap_or((pattern.width, pattern.height));
+        // A hand-written header can request an absurd grid; reject
+        // anything past what the index math handles rather than panic
+        // on overflow or attempt the allocation.
+        if width as u64 * height as u64 > MAX_CELLS {
+            return Err(RleError::InvalidHeader(format!("x = {width}, y = {height}")));
+        }
+        let mut world = World::from_cells(
+            width,
+            height,
+            &vec![false; width as usize * height as usize],
+        );
+        world.rule = pattern.rule.unwrap_or(Rule::CONWAY);
+        let offset_x = ((width as i64 - pattern.width as i64) / 2) as i32;
+        let offset_y = ((height as i64 - pattern.height as i64) / 2) as i32;
+        world.stamp(&pattern.cells, offset_x, offset_y);
+        Ok(world)
+    }
+
+    /// Builds a world from a plaintext `.cells` pattern, sized to the
+    /// pattern's bounding box.
+    pub fn load_cells(reader: impl BufRead) -> io::Result<World> {
+        Self::load_cells_impl(reader, None)
+    }
+
+    /// Builds a world of the given dimensions from a plaintext `.cells`
+    /// pattern, placing the pattern at the center of the grid. Cells
+    /// falling outside the grid are clipped.
+    pub fn load_cells_sized(reader: impl BufRead, width: u32, height: u32) -> io::Result<World> {
+        Self::load_cells_impl(reader, Some((width, height)))
+    }
+
+    fn load_cells_impl(reader: impl BufRead, size: Option<(u32, u32)>) -> io::Result<World> {
+        let pattern = parse_cells(reader)?;
+        let (width, height) = size.unwrap_or((pattern.width, pattern.height));
+        let mut world = World::from_cells(width, height, &vec![false; (width * height) as usize]);
+        let offset_x = ((width as i64 - pattern.width as i64) / 2) as i32;
+        let offset_y = ((height as i64 - pattern.height as i64) / 2) as i32;
+        world.stamp(&pattern.cells, offset_x, offset_y);
+        Ok(world)
+    }
+
+    /// Writes the bounding box of live cells in the plaintext `.cells`
+    /// format: `.` for dead, `O` for alive, one line per row. An empty board
+    /// writes only the comment header.
+    pub fn save_cells(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "!saved by game-of-life-rs")?;
+
+        if let Some((min_x, min_y, max_x, max_y)) = self.live_bounds() {
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    let alive = self.cells.get((y * self.width + x) as usize);
+                    write!(writer, "{}", if alive { 'O' } else { '.' })?;
+                }
+                writeln!(writer)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl World {
+    /// Serializes the full simulation state — dimensions, rule, generation
+    /// counter and all — as JSON.
+    pub fn save_json(&self, writer: impl Write) -> io::Result<()> {
+        serde_json::to_writer(writer, self).map_err(io::Error::from)
+    }
+
+    /// Restores a world previously written by [`World::save_json`].
+    pub fn load_json(reader: impl io::Read) -> io::Result<World> {
+        serde_json::from_reader(reader).map_err(io::Error::from)
+    }
+}
+
+/// Decodes a whole RLE pattern: the header, then the run-length body as
+/// live-cell coordinates relative to the pattern's top-left corner.
+fn parse_rle(reader: impl BufRead) -> Result<RlePattern, RleError> {
+    let mut lines = reader.lines();
+
+    // Skip `#`-prefixed comment lines up to the header.
+    let header = loop {
+        match lines.next() {
+            Some(line) => {
+                let line = line?;
+                if !line.trim().is_empty() && !line.starts_with('#') {
+                    break line;
+                }
+            }
+            None => return Err(RleError::MissingHeader),
+        }
+    };
+
+    let (width, height, rule) = parse_rle_header(&header)?;
+    let mut cells = Vec::new();
+    let mut x: i32 = 0;
+    let mut y: i32 = 0;
+    let mut count: u32 = 0;
+    'body: for line in lines {
+        for c in line?.chars() {
+            match c {
+                // Saturate while digits accumulate; the bound against the
+                // header below rejects anything that large anyway, without
+                // the multiply overflowing first.
+                '0'..='9' => {
+                    count = count.saturating_mul(10).saturating_add(c.to_digit(10).unwrap());
+                }
+                'b' | 'o' => {
+                    let run = count.max(1);
+                    if y as u64 >= height as u64 || x as u64 + run as u64 > width as u64 {
+                        return Err(RleError::RunPastHeader);
+                    }
+                    let run = run as i32;
+                    if c == 'o' {
+                        for i in x..x + run {
+                            cells.push((i, y));
+                        }
+                    }
+                    x += run;
+                    count = 0;
+                }
+                '$' => {
+                    let run = count.max(1);
+                    if y as u64 + run as u64 > height as u64 {
+                        return Err(RleError::RunPastHeader);
+                    }
+                    y += run as i32;
+                    x = 0;
+                    count = 0;
+                }
+                '!' => break 'body,
+                c if c.is_whitespace() => {}
+                c => return Err(RleError::UnexpectedChar(c)),
+            }
+        }
+    }
+
+    Ok(RlePattern {
+        width,
+        height,
+        rule,
+        cells,
+    })
+}
+
+fn parse_cells(reader: impl BufRead) -> io::Result<CellsPattern> {
+    // Collect the pattern rows first; the plaintext format has no
+    // header, so the dimensions come from the rows themselves.
+    let mut rows: Vec<Vec<bool>> = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.starts_with('!') {
+            continue;
+        }
+        let mut row = Vec::with_capacity(line.len());
+        for c in line.trim_end().chars() {
+            match c {
+                '.' => row.push(false),
+                'O' | '*' => row.push(true),
+                c => {
+                    return Err(invalid_data(format!(
+                        "unexpected character {c:?} in pattern body"
+                    )))
+                }
+            }
+        }
+        rows.push(row);
+    }
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0) as u32;
+    let height = rows.len() as u32;
+    if width == 0 || height == 0 {
+        return Err(invalid_data("pattern contains no rows"));
+    }
+
+    let cells = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &alive)| alive)
+                .map(move |(x, _)| (x as i32, y as i32))
+        })
+        .collect();
+    Ok(CellsPattern {
+        width,
+        height,
+        cells,
+    })
+}
+
+/// Parses an RLE header line like `x = 3, y = 3, rule = B3/S23` into the
+/// pattern dimensions and the embedded rule, if any.
+fn parse_rle_header(header: &str) -> Result<(u32, u32, Option<Rule>), RleError> {
+    let mut width = None;
+    let mut height = None;
+    let mut rule = None;
+    for field in header.split(',') {
+        let Some((key, value)) = field.split_once('=') else {
+            return Err(RleError::InvalidHeader(header.to_string()));
+        };
+        match key.trim() {
+            "x" => width = value.trim().parse::<u32>().ok(),
+            "y" => height = value.trim().parse::<u32>().ok(),
+            "rule" => {
+                rule = Some(Rule::parse(value.trim()).map_err(RleError::InvalidRule)?);
+            }
+            _ => {}
+        }
+    }
+
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((width, height, rule)),
+        _ => Err(RleError::InvalidHeader(header.to_string())),
+    }
+}
+
+fn invalid_data(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_life106_centers_the_pattern() {
+        let input = "#Life 1.06\n-1 0\n0 0\n1 0\n";
+        let world = World::load_life106(input.as_bytes(), 5, 5).unwrap();
+        let alive: Vec<bool> = (0..world.cells.len()).map(|i| world.cells.get(i)).collect();
+        #[rustfmt::skip]
+        let expected = [
+            false, false, false, false, false,
+            false, false, false, false, false,
+            false, true,  true,  true,  false,
+            false, false, false, false, false,
+            false, false, false, false, false,
+        ];
+        assert_eq!(alive, expected);
+    }
+
+    #[test]
+    fn load_life106_clips_out_of_range_coordinates() {
+        let input = "0 0\n100 100\n-100 0\n";
+        let world = World::load_life106(input.as_bytes(), 3, 3).unwrap();
+        let num_alive = (0..world.cells.len()).filter(|&i| world.cells.get(i)).count();
+        assert_eq!(num_alive, 1);
+    }
+
+    #[test]
+    fn load_rle_glider() {
+        let input = "#C a glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n";
+        let world = World::load_rle(input.as_bytes()).unwrap();
+        let alive: Vec<bool> = (0..world.cells.len()).map(|i| world.cells.get(i)).collect();
+        #[rustfmt::skip]
+        let expected = [
+            false, true,  false,
+            false, false, true,
+            true,  true,  true,
+        ];
+        assert_eq!(alive, expected);
+    }
+
+    #[test]
+    fn load_rle_sized_centers_the_pattern() {
+        let input = "x = 1, y = 1\no!\n";
+        let world = World::load_rle_sized(input.as_bytes(), 3, 3).unwrap();
+        let alive: Vec<bool> = (0..world.cells.len()).map(|i| world.cells.get(i)).collect();
+        #[rustfmt::skip]
+        let expected = [
+            false, false, false,
+            false, true,  false,
+            false, false, false,
+        ];
+        assert_eq!(alive, expected);
+    }
+
+    #[test]
+    fn load_rle_applies_the_header_rule() {
+        let input = "x = 3, y = 3, rule = B36/S23\n3o!\n";
+        let world = World::load_rle(input.as_bytes()).unwrap();
+        assert_eq!(world.rule, Rule::parse("B36/S23").unwrap());
+
+        let input = "x = 1, y = 1\no!\n";
+        assert_eq!(World::load_rle(input.as_bytes()).unwrap().rule, Rule::CONWAY);
+
+        let input = "x = 1, y = 1, rule = B9/S\no!\n";
+        assert!(matches!(
+            World::load_rle(input.as_bytes()),
+            Err(RleError::InvalidRule(_))
+        ));
+    }
+
+    #[test]
+    fn load_rle_rejects_bad_input() {
+        assert!(matches!(
+            World::load_rle("".as_bytes()),
+            Err(RleError::MissingHeader)
+        ));
+        assert!(matches!(
+            World::load_rle("bob$2bo$3o!\n".as_bytes()),
+            Err(RleError::InvalidHeader(_))
+        ));
+        assert!(matches!(
+            World::load_rle("x = 3, y = 3\nboz!\n".as_bytes()),
+            Err(RleError::UnexpectedChar('z'))
+        ));
+    }
+
+    #[test]
+    fn load_rle_rejects_runs_past_the_header() {
+        // A crafted run count must not overflow or drive a huge
+        // allocation past what the header declared.
+        for input in [
+            "x = 1, y = 1\n99999999999o!\n",
+            "x = 1, y = 1\n4294967295o!\n",
+            "x = 3, y = 1\n4o!\n",
+            "x = 1, y = 2\no$$o!\n",
+        ] {
+            assert!(matches!(
+                World::load_rle(input.as_bytes()),
+                Err(RleError::RunPastHeader)
+            ));
+        }
+    }
+
+    #[test]
+    fn load_rle_rejects_oversized_headers() {
+        let input = "x = 100000, y = 100000\no!\n";
+        assert!(matches!(
+            World::load_rle(input.as_bytes()),
+            Err(RleError::InvalidHeader(_))
+        ));
+        assert!(matches!(
+            World::load_rle_sized("x = 1, y = 1\no!\n".as_bytes(), 100000, 100000),
+            Err(RleError::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn load_cells_reads_plaintext_patterns() {
+        let input = "!Name: Glider\n.O.\n..O\nOOO\n";
+        let world = World::load_cells(input.as_bytes()).unwrap();
+        let alive: Vec<bool> = (0..world.cells.len()).map(|i| world.cells.get(i)).collect();
+        #[rustfmt::skip]
+        let expected = [
+            false, true,  false,
+            false, false, true,
+            true,  true,  true,
+        ];
+        assert_eq!(alive, expected);
+
+        assert!(World::load_cells("!empty\n".as_bytes()).is_err());
+        assert!(World::load_cells(".x.\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn load_cells_sized_centers_the_pattern() {
+        let world = World::load_cells_sized("O\n".as_bytes(), 3, 3).unwrap();
+        let alive: Vec<bool> = (0..world.cells.len()).map(|i| world.cells.get(i)).collect();
+        #[rustfmt::skip]
+        let expected = [
+            false, false, false,
+            false, true,  false,
+            false, false, false,
+        ];
+        assert_eq!(alive, expected);
+    }
+
+    #[test]
+    fn pattern_readers_return_relative_coordinates() {
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+
+        let rle = "x = 3, y = 3\nbob$2bo$3o!\n";
+        assert_eq!(load_rle_pattern(rle.as_bytes()).unwrap(), glider);
+
+        let cells = ".O.\n..O\nOOO\n";
+        assert_eq!(load_cells_pattern(cells.as_bytes()).unwrap(), glider);
+
+        let life106 = "#Life 1.06\n-1 0\n0 0\n1 0\n";
+        assert_eq!(
+            load_life106_pattern(life106.as_bytes()).unwrap(),
+            [(-1, 0), (0, 0), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn stamped_patterns_or_together() {
+        let mut world = World::from_cells(8, 8, &[false; 64]);
+        let block = load_cells_pattern("OO\nOO\n".as_bytes()).unwrap();
+        world.stamp(&block, 1, 1);
+        world.stamp(&block, 2, 2);
+        let num_alive = (0..world.cells.len()).filter(|&i| world.cells.get(i)).count();
+        assert_eq!(num_alive, 7);
+    }
+
+    #[test]
+    fn save_cells_writes_the_live_bounding_box() {
+        #[rustfmt::skip]
+        let cells = [
+            false, false, false, false,
+            false, true,  true,  false,
+            false, false, true,  false,
+            false, false, false, false,
+        ];
+        let world = World::from_cells(4, 4, &cells);
+        let mut out = Vec::new();
+        world.save_cells(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "!saved by game-of-life-rs\nOO\n.O\n"
+        );
+    }
+
+    #[test]
+    fn save_cells_empty_board_writes_only_the_header() {
+        let world = World::from_cells(2, 2, &[false; 4]);
+        let mut out = Vec::new();
+        world.save_cells(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "!saved by game-of-life-rs\n");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip_preserves_simulation_state() {
+        let mut world = World::from_cells(4, 4, &[true; 16]);
+        world.rule = crate::Rule::parse("B36/S23").unwrap();
+        world.edge_mode = crate::EdgeMode::Wrap;
+        world.update();
+
+        let mut out = Vec::new();
+        world.save_json(&mut out).unwrap();
+        let restored = World::load_json(out.as_slice()).unwrap();
+
+        assert_eq!(restored.width, world.width);
+        assert_eq!(restored.height, world.height);
+        assert_eq!(restored.rule, world.rule);
+        assert_eq!(restored.edge_mode, world.edge_mode);
+        assert_eq!(restored.generation, world.generation);
+        assert_eq!(restored.population, world.population);
+        assert_eq!(restored.cells, world.cells);
+        assert_eq!(restored.ages, world.ages);
+    }
+
+    #[test]
+    fn load_life106_rejects_malformed_lines() {
+        assert!(World::load_life106("0\n".as_bytes(), 3, 3).is_err());
+        assert!(World::load_life106("a b\n".as_bytes(), 3, 3).is_err());
+    }
+}
@@ -0,0 +1,2586 @@
+#![deny(clippy::all)]
+#![forbid(unsafe_code)]
+
+//! Conway's Game of Life simulation core, independent of any rendering
+//! or windowing concerns.
+
+mod formats;
+pub mod patterns;
+mod replay;
+mod sparse;
+
+pub use formats::{
+    load_cells_pattern, load_life106_pattern, load_rle_pattern, load_rle_pattern_with_rule,
+    RleError,
+};
+pub use replay::{Replay, ReplayRecorder};
+pub use sparse::SparseWorld;
+
+use rayon::prelude::*;
+use std::fmt;
+
+/// Age at which the live-cell color gradient saturates.
+const AGE_CAP: u8 = 60;
+
+/// Number of generations retained for undo.
+const HISTORY_DEPTH: usize = 32;
+
+/// Cells added on a side when the world grows automatically.
+const GROW_MARGIN: u32 = 16;
+
+/// Edge length of the square tiles used to skip quiescent regions in
+/// `update`. Must not be smaller than the neighbourhood radius, or tile
+/// adjacency would miss changes reaching across a whole tile.
+const TILE_SIZE: u32 = 16;
+
+/// Most cells a grid may hold; requests beyond this are clamped so the
+/// flat index math cannot overflow on any target.
+const MAX_CELLS: u64 = 1 << 26;
+
+/// Behavior shared by the dense and sparse simulation backends, so that
+/// driver code can stay backend-agnostic. Coordinates are signed; dense
+/// backends treat cells outside their grid as dead.
+pub trait Simulation {
+    fn update(&mut self);
+    fn generation(&self) -> u64;
+    fn population(&self) -> usize;
+    fn get(&self, x: i64, y: i64) -> bool;
+    fn set_cell(&mut self, x: i64, y: i64, alive: bool);
+}
+
+/// Errors produced while parsing a rule string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleParseError {
+    /// The birth section (`B...`) is missing.
+    MissingBirth,
+    /// The survival section (`S...`) is missing.
+    MissingSurvival,
+    /// A neighbour count above 8.
+    DigitOutOfRange(char),
+    /// A character that is neither a section prefix nor a digit.
+    UnexpectedChar(char),
+    /// A Generations state count that is not a number of at least 2.
+    InvalidStates(String),
+    /// The two sections mix prefixed and bare notation, so the intended
+    /// ordering is ambiguous.
+    MixedNotation,
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleParseError::MissingBirth => {
+                write!(f, "missing birth section: expected `B` digits, as in `B3/S23`")
+            }
+            RuleParseError::MissingSurvival => {
+                write!(f, "missing survival section: expected `S` digits, as in `B3/S23`")
+            }
+            RuleParseError::DigitOutOfRange(c) => {
+                write!(f, "neighbour count {c:?} is out of range: counts go up to 8")
+            }
+            RuleParseError::UnexpectedChar(c) => {
+                write!(f, "unexpected character {c:?} in rule string")
+            }
+            RuleParseError::InvalidStates(part) => {
+                write!(f, "invalid state count {part:?}: expected a number of at least 2")
+            }
+            RuleParseError::MixedNotation => {
+                write!(f, "ambiguous rule notation: use `B3/S23`, `S23/B3`, or `23/3`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RuleParseError {}
+
+/// A cellular automaton rule in B/S notation, storing the neighbour counts
+/// that cause a birth or a survival as bitmasks over 0..=8. Rules with
+/// more than two states are "Generations" rules: a cell that fails to
+/// survive steps through `states - 2` dying stages before it is dead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rule {
+    pub birth: u16,
+    pub survival: u16,
+    pub states: u8,
+}
+
+impl Rule {
+    /// Conway's original rule, B3/S23.
+    pub const CONWAY: Rule = Rule {
+        birth: 1 << 3,
+        survival: (1 << 2) | (1 << 3),
+        states: 2,
+    };
+
+    /// Brian's Brain, B2/S/C3: cells born next to exactly two live cells
+    /// always spend one generation dying before they are dead.
+    pub const BRIANS_BRAIN: Rule = Rule {
+        birth: 1 << 2,
+        survival: 0,
+        states: 3,
+    };
+
+    /// HighLife, B36/S23: Conway plus birth on six, famous for its
+    /// small replicator.
+    pub const HIGHLIFE: Rule = Rule {
+        birth: (1 << 3) | (1 << 6),
+        survival: (1 << 2) | (1 << 3),
+        states: 2,
+    };
+
+    /// Day & Night, B3678/S34678: symmetric under swapping live and
+    /// dead cells.
+    pub const DAY_AND_NIGHT: Rule = Rule {
+        birth: (1 << 3) | (1 << 6) | (1 << 7) | (1 << 8),
+        survival: (1 << 3) | (1 << 4) | (1 << 6) | (1 << 7) | (1 << 8),
+        states: 2,
+    };
+
+    /// Seeds, B2/S: every live cell dies, so patterns explode.
+    pub const SEEDS: Rule = Rule {
+        birth: 1 << 2,
+        survival: 0,
+        states: 2,
+    };
+
+    /// Replicator, B1357/S1357: every pattern eventually copies itself.
+    pub const REPLICATOR: Rule = Rule {
+        birth: (1 << 1) | (1 << 3) | (1 << 5) | (1 << 7),
+        survival: (1 << 1) | (1 << 3) | (1 << 5) | (1 << 7),
+        states: 2,
+    };
+
+    /// Named presets for cycling through live, Conway first.
+    pub const PRESETS: [(&'static str, Rule); 5] = [
+        ("Conway", Self::CONWAY),
+        ("HighLife", Self::HIGHLIFE),
+        ("Day & Night", Self::DAY_AND_NIGHT),
+        ("Seeds", Self::SEEDS),
+        ("Replicator", Self::REPLICATOR),
+    ];
+
+    /// Parses a rule string in any of the common orderings — `"B3/S23"`,
+    /// `"S23/B3"`, or the bare survival/birth form `"23/3"` — with an
+    /// optional Generations state count as in `"B2/S/C3"`.
+    pub fn parse(s: &str) -> Result<Rule, RuleParseError> {
+        if s.trim().is_empty() {
+            return Err(RuleParseError::MissingBirth);
+        }
+        let mut parts = s.split('/');
+        let first = parts.next().unwrap_or("");
+        let second = parts.next().ok_or(RuleParseError::MissingSurvival)?;
+        let (birth, survival) = match (
+            first.strip_prefix(['B', 'b']),
+            first.strip_prefix(['S', 's']),
+            second.strip_prefix(['B', 'b']),
+            second.strip_prefix(['S', 's']),
+        ) {
+            (Some(birth), _, _, Some(survival)) => (birth, survival),
+            (_, Some(survival), Some(birth), _) => (birth, survival),
+            // The bare form puts survival first, as in `23/3`.
+            (None, None, None, None) => (second, first),
+            _ => return Err(RuleParseError::MixedNotation),
+        };
+        let birth = Self::parse_counts(birth)?;
+        let survival = Self::parse_counts(survival)?;
+        let states = match parts.next() {
+            Some(part) => {
+                let digits = part.strip_prefix(['C', 'c']).unwrap_or(part);
+                match digits.parse::<u8>() {
+                    Ok(states) if states >= 2 => states,
+                    _ => return Err(RuleParseError::InvalidStates(part.to_string())),
+                }
+            }
+            None => 2,
+        };
+        if parts.next().is_some() {
+            return Err(RuleParseError::UnexpectedChar('/'));
+        }
+        Ok(Rule {
+            birth,
+            survival,
+            states,
+        })
+    }
+
+    fn parse_counts(digits: &str) -> Result<u16, RuleParseError> {
+        let mut mask = 0;
+        for c in digits.chars() {
+            let n = c.to_digit(10).ok_or(RuleParseError::UnexpectedChar(c))?;
+            if n > 8 {
+                return Err(RuleParseError::DigitOutOfRange(c));
+            }
+            mask |= 1 << n;
+        }
+        Ok(mask)
+    }
+
+    pub fn born(&self, num_neighbours: u8) -> bool {
+        self.birth & (1 << num_neighbours) != 0
+    }
+
+    pub fn survives(&self, num_neighbours: u8) -> bool {
+        self.survival & (1 << num_neighbours) != 0
+    }
+}
+
+/// How a random fill distributes its density across the grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FillMode {
+    /// The same live probability everywhere.
+    #[default]
+    Uniform,
+    /// Densest at the left edge, fading to empty at the right.
+    Horizontal,
+    /// Densest at the center, fading towards the corners.
+    Radial,
+}
+
+/// How neighbour lookups treat coordinates beyond the grid boundary.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeMode {
+    /// Out-of-bounds neighbours are dead.
+    #[default]
+    Dead,
+    /// Coordinates wrap around, joining opposite edges into a torus.
+    Wrap,
+    /// The boundary reflects, so an edge cell's missing neighbours are
+    /// copies of its in-bounds ones.
+    Mirror,
+}
+
+/// The set of cells considered adjacent when counting neighbours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Neighbourhood {
+    /// All cells within Chebyshev distance `radius` (8 cells at radius 1).
+    Moore { radius: u8 },
+    /// All cells within Manhattan distance `radius` (4 cells at radius 1).
+    VonNeumann { radius: u8 },
+    /// The six adjacent cells of a hexagonal tiling, mapped onto the
+    /// square grid with odd rows shifted half a cell right ("odd-r"
+    /// offset coordinates). Birth and survival sets are interpreted over
+    /// 0..=6 neighbours.
+    Hex,
+}
+
+impl Neighbourhood {
+    /// The classic 8-cell Moore neighbourhood used by Conway's Life.
+    pub const MOORE: Neighbourhood = Neighbourhood::Moore { radius: 1 };
+
+    fn radius(&self) -> isize {
+        match self {
+            Neighbourhood::Moore { radius } | Neighbourhood::VonNeumann { radius } => {
+                *radius as isize
+            }
+            Neighbourhood::Hex => 1,
+        }
+    }
+
+    /// Whether the cell at `(dx, dy)` relative to a cell on an odd or
+    /// even row belongs to the neighbourhood. Only the hex neighbourhood
+    /// depends on the row parity.
+    fn contains(&self, dx: isize, dy: isize, odd_row: bool) -> bool {
+        match self {
+            Neighbourhood::Moore { radius } => {
+                dx.abs().max(dy.abs()) <= *radius as isize
+            }
+            Neighbourhood::VonNeumann { radius } => dx.abs() + dy.abs() <= *radius as isize,
+            Neighbourhood::Hex => match dy {
+                0 => dx.abs() == 1,
+                -1 | 1 if odd_row => dx == 0 || dx == 1,
+                -1 | 1 => dx == -1 || dx == 0,
+                _ => false,
+            },
+        }
+    }
+}
+
+/// The colors used by `draw`, as RGBA values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Palette {
+    /// Freshly born live cells; live cells blend towards `old` as they age.
+    pub alive: [u8; 4],
+    /// Live cells that have reached the age cap.
+    pub old: [u8; 4],
+    pub dead: [u8; 4],
+}
+
+impl Palette {
+    pub const DEFAULT: Palette = Palette {
+        alive: [0x5e, 0x48, 0xe8, 0xff],
+        old: [0xe8, 0x48, 0x5e, 0xff],
+        dead: [0x48, 0xb2, 0xe8, 0xff],
+    };
+
+    /// Grayscale: white cells fading towards gray on black.
+    pub const MONOCHROME: Palette = Palette {
+        alive: [0xff, 0xff, 0xff, 0xff],
+        old: [0x80, 0x80, 0x80, 0xff],
+        dead: [0x00, 0x00, 0x00, 0xff],
+    };
+
+    /// Maximum contrast: pure white on black with no age gradient.
+    pub const HIGH_CONTRAST: Palette = Palette {
+        alive: [0xff, 0xff, 0xff, 0xff],
+        old: [0xff, 0xff, 0xff, 0xff],
+        dead: [0x00, 0x00, 0x00, 0xff],
+    };
+
+    /// Colorblind-safe blue and orange from the Okabe-Ito palette.
+    pub const COLORBLIND_SAFE: Palette = Palette {
+        alive: [0x00, 0x72, 0xb2, 0xff],
+        old: [0xe6, 0x9f, 0x00, 0xff],
+        dead: [0xf0, 0xf0, 0xf0, 0xff],
+    };
+
+    /// The built-in presets, in the order the UI cycles through them.
+    pub const PRESETS: [Palette; 4] = [
+        Palette::DEFAULT,
+        Palette::MONOCHROME,
+        Palette::HIGH_CONTRAST,
+        Palette::COLORBLIND_SAFE,
+    ];
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The region of the world mapped onto the rendered frame: `x` and `y`
+/// are the world coordinates of the cell under the frame's top-left
+/// pixel, and the scales are the on-screen size of a cell in pixels. The
+/// two axes scale independently, so cells can render as rectangles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Viewport {
+    pub x: i64,
+    pub y: i64,
+    pub scale_x: u32,
+    pub scale_y: u32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            scale_x: 1,
+            scale_y: 1,
+        }
+    }
+}
+
+/// A rectangle of the grid that runs its own rule instead of the
+/// world's default, registered through [`World::add_rule_region`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuleRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub rule: Rule,
+}
+
+/// A bit-packed grid of cell states, one bit per cell. Storing a single
+/// bit per cell keeps large worlds compact and cache-friendly.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BitGrid {
+    words: Vec<u64>,
+    num_bits: usize,
+}
+
+impl BitGrid {
+    pub fn new(num_bits: usize) -> Self {
+        Self {
+            words: vec![0; num_bits.div_ceil(64)],
+            num_bits,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.num_bits
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_bits == 0
+    }
+
+    /// Reads a bit. Out-of-range indices read as dead, with a warning,
+    /// so a dimension mismatch degrades instead of killing the window.
+    pub fn get(&self, i: usize) -> bool {
+        if i >= self.num_bits {
+            log::warn!("bit index {i} out of range for grid of {}", self.num_bits);
+            return false;
+        }
+        (self.words[i / 64] >> (i % 64)) & 1 != 0
+    }
+
+    /// Writes a bit. Out-of-range indices are dropped, with a warning.
+    pub fn set(&mut self, i: usize, alive: bool) {
+        if i >= self.num_bits {
+            log::warn!("bit index {i} out of range for grid of {}", self.num_bits);
+            return;
+        }
+        let mask = 1 << (i % 64);
+        if alive {
+            self.words[i / 64] |= mask;
+        } else {
+            self.words[i / 64] &= !mask;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct World {
+    width: u32,
+    height: u32,
+    pub edge_mode: EdgeMode,
+    /// When set, the grid grows by [`GROW_MARGIN`] cells on any side that
+    /// live cells reach, up to the given `(width, height)` limit. Growing
+    /// does not combine meaningfully with wrapped edges.
+    pub grow_limit: Option<(u32, u32)>,
+    rule: Rule,
+    pub neighbourhood: Neighbourhood,
+    /// The window onto the world used by `draw`; panning and zooming are
+    /// adjustments to this viewport, not to the world itself.
+    pub viewport: Viewport,
+    pub palette: Palette,
+    generation: u64,
+    /// When set, `draw` darkens the pixel rows and columns that fall on
+    /// cell boundaries to show a faint grid overlay.
+    pub grid_overlay: bool,
+    /// Deliberately-wrong teaching mode: `update` reads and writes the
+    /// same buffer, so earlier cells in the scan influence later ones
+    /// within a generation. Illustrates why double buffering matters.
+    pub single_buffer: bool,
+    /// When set, `draw` tints the dead-cell background towards the live
+    /// color in proportion to the last population delta, so a busy board
+    /// visibly pulses while a calm one stays at the base color.
+    pub activity_pulse: bool,
+    /// When set, `update` accumulates per-phase wall-clock timings,
+    /// readable through [`profile_timings`](Self::profile_timings). Off
+    /// by default so the timer calls cost nothing in normal runs.
+    pub profile: bool,
+    /// Rules overriding the default inside rectangular regions, applied
+    /// in insertion order so a later region wins where they overlap.
+    rule_regions: Vec<RuleRegion>,
+    /// Per-cell index into `rule_regions` plus one, with zero meaning
+    /// the default rule; rebuilt by `update` when it no longer matches
+    /// the grid. Empty while there are no regions, so the single-rule
+    /// fast path costs one failed bounds check per cell.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    rule_map: Vec<u8>,
+    /// Rainbow coloring: `draw` shows each live cell's inherited color
+    /// from `colors` instead of the age gradient, and every birth
+    /// averages the colors of the neighbours that caused it, so gliders
+    /// carry their hue across the board.
+    pub rainbow: bool,
+    /// Per-cell inherited color, maintained while `rainbow` is set;
+    /// seeded lazily from a hue wheel over the cell index.
+    colors: Vec<[u8; 4]>,
+    /// Detected cycle length of the board, if any; readable through
+    /// [`period`](Self::period).
+    period: Option<u8>,
+    /// Number of live cells, maintained incrementally as cells are born
+    /// and die.
+    population: usize,
+    /// Change in population caused by the last `update`.
+    population_delta: i64,
+    cells: BitGrid,
+    /// Cells frozen as immutable walls: `update` never changes their
+    /// state, while neighbour counting still sees whatever state they
+    /// hold. `clear` and `randomize` leave them untouched.
+    frozen: BitGrid,
+    /// Number of consecutive generations each cell has been alive, capped
+    /// at [`AGE_CAP`]; zero for dead cells.
+    ages: Vec<u8>,
+    /// Remaining dying stages per cell under a Generations rule; zero for
+    /// live and fully dead cells.
+    decay: Vec<u8>,
+    /// The previous generation's cell states, kept for still-life detection.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    prev_cells: BitGrid,
+    /// The cell states from two generations ago, kept for period-2
+    /// oscillation detection.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    prev_prev_cells: BitGrid,
+    /// Scratch buffer of per-cell neighbour counts, reused across
+    /// generations to avoid reallocating every frame.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    neighbours: Vec<u8>,
+    /// Recent generations for undo, oldest first and bounded at
+    /// [`HISTORY_DEPTH`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    history: std::collections::VecDeque<Snapshot>,
+    /// Flattened per-cell neighbour index table. For a fixed geometry the
+    /// neighbours of every cell are constant, so they are resolved once
+    /// and `update` just sums live bits over the stored indices.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    offsets: OffsetTable,
+    /// Tiles of [`TILE_SIZE`] in which any cell changed during the last
+    /// update. A tile whose neighbourhood saw no change is quiescent and
+    /// its next state is its current one, so `update` skips it.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    tile_changed: Vec<bool>,
+    /// Set by out-of-band board edits to make the next update recompute
+    /// every tile, since the change map no longer reflects the cells.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    tiles_stale: bool,
+    /// Per-phase timings accumulated while `profile` is set.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    timings: UpdateTimings,
+}
+
+/// Wall-clock time spent in each phase of `update`, summed over every
+/// generation stepped while [`World::profile`] was set.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UpdateTimings {
+    /// Total time counting neighbours.
+    pub neighbours: std::time::Duration,
+    /// Total time applying the rule to produce the next generation.
+    pub rule: std::time::Duration,
+    /// Number of updates the totals cover.
+    pub updates: u64,
+}
+
+/// A precomputed neighbour index table, tagged with the grid geometry it
+/// was built for so it can be rebuilt when that changes.
+#[derive(Clone, Default)]
+struct OffsetTable {
+    key: Option<(u32, u32, EdgeMode, Neighbourhood)>,
+    /// Start of each cell's run in `indices`, with a trailing sentinel.
+    starts: Vec<u32>,
+    indices: Vec<u32>,
+}
+
+/// Brings requested grid dimensions into the range the index math can
+/// handle: zero sides become 1, and dimensions whose product exceeds
+/// [`MAX_CELLS`] are halved until it fits, with a logged warning.
+fn clamped_dimensions(width: u32, height: u32) -> (u32, u32) {
+    let requested = (width, height);
+    let (mut width, mut height) = (width.max(1), height.max(1));
+    while width as u64 * height as u64 > MAX_CELLS {
+        if width >= height {
+            width /= 2;
+        } else {
+            height /= 2;
+        }
+    }
+    if (width, height) != requested {
+        log::warn!(
+            "requested {}x{} grid clamped to {width}x{height}",
+            requested.0,
+            requested.1
+        );
+    }
+    (width, height)
+}
+
+/// A generation retained in the undo history.
+#[derive(Clone)]
+struct Snapshot {
+    cells: BitGrid,
+    ages: Vec<u8>,
+    decay: Vec<u8>,
+    population: usize,
+    population_delta: i64,
+    period: Option<u8>,
+}
+
+impl World {
+    pub fn new(
+        width: u32,
+        height: u32,
+        fill_rate: f32,
+        edge_mode: EdgeMode,
+        rng: &mut fastrand::Rng,
+    ) -> Self {
+        let (width, height) = clamped_dimensions(width, height);
+        let num_cells = width as usize * height as usize;
+        let cells = BitGrid::new(num_cells);
+
+        let mut world = Self {
+            width,
+            height,
+            edge_mode,
+            grow_limit: None,
+            rule: Rule::CONWAY,
+            neighbourhood: Neighbourhood::MOORE,
+            viewport: Viewport::default(),
+            palette: Palette::DEFAULT,
+            generation: 0,
+            grid_overlay: false,
+            single_buffer: false,
+            activity_pulse: false,
+            profile: false,
+            rule_regions: Vec::new(),
+            rule_map: Vec::new(),
+            rainbow: false,
+            colors: Vec::new(),
+            period: None,
+            population: 0,
+            population_delta: 0,
+            cells,
+            frozen: BitGrid::new(num_cells),
+            prev_cells: BitGrid::new(num_cells),
+            prev_prev_cells: BitGrid::new(num_cells),
+            ages: vec![0; num_cells],
+            decay: vec![0; num_cells],
+            neighbours: Vec::new(),
+            history: std::collections::VecDeque::new(),
+            offsets: OffsetTable::default(),
+            tile_changed: Vec::new(),
+            tiles_stale: true,
+            timings: UpdateTimings::default(),
+        };
+        world.randomize(fill_rate, rng);
+        world
+    }
+
+    pub fn from_cells(width: u32, height: u32, alive: &[bool]) -> Self {
+        assert_eq!(
+            width as u64 * height as u64,
+            alive.len() as u64,
+            "cell layout does not match dimensions"
+        );
+        let mut cells = BitGrid::new(alive.len());
+        for (i, &alive) in alive.iter().enumerate() {
+            cells.set(i, alive);
+        }
+        let ages = alive.iter().map(|&alive| alive as u8).collect();
+
+        Self {
+            width,
+            height,
+            edge_mode: EdgeMode::Dead,
+            grow_limit: None,
+            rule: Rule::CONWAY,
+            neighbourhood: Neighbourhood::MOORE,
+            viewport: Viewport::default(),
+            palette: Palette::DEFAULT,
+            generation: 0,
+            grid_overlay: false,
+            single_buffer: false,
+            activity_pulse: false,
+            profile: false,
+            rule_regions: Vec::new(),
+            rule_map: Vec::new(),
+            rainbow: false,
+            colors: Vec::new(),
+            period: None,
+            population: alive.iter().filter(|&&alive| alive).count(),
+            population_delta: 0,
+            prev_cells: BitGrid::new(alive.len()),
+            prev_prev_cells: BitGrid::new(alive.len()),
+            cells,
+            frozen: BitGrid::new(alive.len()),
+            ages,
+            decay: vec![0; alive.len()],
+            neighbours: Vec::new(),
+            history: std::collections::VecDeque::new(),
+            offsets: OffsetTable::default(),
+            tile_changed: Vec::new(),
+            tiles_stale: true,
+            timings: UpdateTimings::default(),
+        }
+    }
+
+    pub fn randomize(&mut self, fill_rate: f32, rng: &mut fastrand::Rng) {
+        self.randomize_with(fill_rate, FillMode::Uniform, rng);
+    }
+
+    /// Randomizes the board with a per-cell live probability shaped by the
+    /// fill mode, peaking at `fill_rate`.
+    pub fn randomize_with(&mut self, fill_rate: f32, mode: FillMode, rng: &mut fastrand::Rng) {
+        let center_x = self.width.saturating_sub(1) as f32 / 2.0;
+        let center_y = self.height.saturating_sub(1) as f32 / 2.0;
+        let max_distance = center_x.hypot(center_y).max(1.0);
+        self.population = 0;
+        for i in 0..self.cells.len() {
+            if self.frozen.get(i) {
+                self.population += self.cells.get(i) as usize;
+                continue;
+            }
+            let x = (i as u32 % self.width) as f32;
+            let y = (i as u32 / self.width) as f32;
+            let probability = match mode {
+                FillMode::Uniform => fill_rate,
+                FillMode::Horizontal => fill_rate * (1.0 - x / self.width as f32),
+                FillMode::Radial => {
+                    fill_rate * (1.0 - (x - center_x).hypot(y - center_y) / max_distance)
+                }
+            };
+            let alive = rng.f32() < probability;
+            self.cells.set(i, alive);
+            self.ages[i] = alive as u8;
+            self.decay[i] = 0;
+            self.population += alive as usize;
+        }
+        self.population_delta = 0;
+        self.period = None;
+        self.generation = 0;
+        self.history.clear();
+        self.tiles_stale = true;
+    }
+
+    /// Flips each non-frozen cell with the given probability, regardless
+    /// of the rule. Run after [`World::update`] for noisy "warm" variants;
+    /// at a probability of zero the simulation is exactly deterministic.
+    pub fn apply_noise(&mut self, probability: f32, rng: &mut fastrand::Rng) {
+        if probability <= 0.0 {
+            return;
+        }
+        for i in 0..self.cells.len() {
+            if self.frozen.get(i) || rng.f32() >= probability {
+                continue;
+            }
+            let alive = !self.cells.get(i);
+            self.cells.set(i, alive);
+            self.ages[i] = alive as u8;
+            self.decay[i] = 0;
+            if alive {
+                self.population += 1;
+            } else {
+                self.population -= 1;
+            }
+            self.tiles_stale = true;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for i in 0..self.cells.len() {
+            if !self.frozen.get(i) {
+                self.cells.set(i, false);
+                self.ages[i] = 0;
+                self.decay[i] = 0;
+            }
+        }
+        self.population = (0..self.cells.len())
+            .filter(|&i| self.cells.get(i))
+            .count();
+        self.population_delta = 0;
+        self.period = None;
+        self.generation = 0;
+        self.history.clear();
+        self.tiles_stale = true;
+    }
+
+    /// Flips every cell's alive state, showing the complement of the
+    /// board. Frozen walls keep their state, like `clear` and
+    /// `randomize`; newly live cells start at age 1.
+    pub fn invert(&mut self) {
+        for i in 0..self.cells.len() {
+            if self.frozen.get(i) {
+                continue;
+            }
+            let alive = !self.cells.get(i);
+            self.cells.set(i, alive);
+            self.ages[i] = alive as u8;
+            self.decay[i] = 0;
+        }
+        self.population = (0..self.cells.len())
+            .filter(|&i| self.cells.get(i))
+            .count();
+        self.period = None;
+        self.tiles_stale = true;
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        if x >= self.width || y >= self.height {
+            log::warn!("cell ({x}, {y}) outside {}x{} grid", self.width, self.height);
+            return false;
+        }
+        self.cells.get((y * self.width + x) as usize)
+    }
+
+    /// The grid dimensions as `(width, height)` in cells.
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Grid width in cells.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Grid height in cells.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Number of generations stepped since the world was created.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The active cellular automaton rule.
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Replaces the rule, sanitizing values a hand-built [`Rule`] could
+    /// hold that [`Rule::parse`] would reject: neighbour counts above 8
+    /// are dropped and a state count below 2 is raised to 2, with a
+    /// warning.
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = sanitized_rule(rule);
+    }
+
+    /// Registers a region that runs its own rule instead of the default,
+    /// sanitized like [`set_rule`](Self::set_rule). Later regions win
+    /// where rectangles overlap; parts reaching past the grid are
+    /// ignored. The per-cell lookup is rebuilt on the next update.
+    pub fn add_rule_region(&mut self, mut region: RuleRegion) {
+        if self.rule_regions.len() >= u8::MAX as usize {
+            log::warn!("rule region limit of {} reached", u8::MAX);
+            return;
+        }
+        region.rule = sanitized_rule(region.rule);
+        self.rule_regions.push(region);
+        self.rule_map.clear();
+    }
+
+    /// The registered rule regions, in application order.
+    pub fn rule_regions(&self) -> &[RuleRegion] {
+        &self.rule_regions
+    }
+
+    /// Drops every rule region, returning the whole grid to the
+    /// default rule.
+    pub fn clear_rule_regions(&mut self) {
+        self.rule_regions.clear();
+        self.rule_map.clear();
+    }
+
+    /// Number of live cells. Maintained incrementally as cells are born
+    /// and die, so this never rescans the grid.
+    pub fn population(&self) -> usize {
+        self.population
+    }
+
+    /// Change in population caused by the last `update`.
+    pub fn population_delta(&self) -> i64 {
+        self.population_delta
+    }
+
+    /// Detected cycle length of the board, if any: `Some(1)` means the last
+    /// `update` left the board unchanged (a still life), `Some(2)` means it
+    /// matches the generation from two steps ago (a period-2 oscillation).
+    pub fn period(&self) -> Option<u8> {
+        self.period
+    }
+
+    /// Iterates over the `(x, y)` coordinates of every live cell, in
+    /// row-major order.
+    pub fn live_cells(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        (0..self.cells.len())
+            .filter(|&i| self.cells.get(i))
+            .map(|i| (i as u32 % self.width, i as u32 / self.width))
+    }
+
+    /// The bounding box of the live population as `(min_x, min_y, max_x,
+    /// max_y)`, or `None` for an empty board.
+    pub fn live_bounds(&self) -> Option<(u32, u32, u32, u32)> {
+        self.live_cells()
+            .fold(None, |bounds, (x, y)| {
+                let (min_x, min_y, max_x, max_y) = bounds.unwrap_or((x, y, x, y));
+                Some((min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)))
+            })
+    }
+
+    pub fn set_cell(&mut self, x: u32, y: u32, alive: bool) {
+        if x < self.width && y < self.height {
+            let i = (y * self.width + x) as usize;
+            let was_alive = self.cells.get(i);
+            self.population = self.population - was_alive as usize + alive as usize;
+            self.cells.set(i, alive);
+            self.ages[i] = alive as u8;
+            self.decay[i] = 0;
+            self.tiles_stale = true;
+        }
+    }
+
+    /// Marks or unmarks a cell as a frozen wall. Freezing does not change
+    /// the cell's current state, only pins it.
+    pub fn set_frozen(&mut self, x: u32, y: u32, frozen: bool) {
+        if x < self.width && y < self.height {
+            self.frozen.set((y * self.width + x) as usize, frozen);
+            self.tiles_stale = true;
+        }
+    }
+
+    pub fn is_frozen(&self, x: u32, y: u32) -> bool {
+        if x >= self.width || y >= self.height {
+            log::warn!("cell ({x}, {y}) outside {}x{} grid", self.width, self.height);
+            return false;
+        }
+        self.frozen.get((y * self.width + x) as usize)
+    }
+
+    /// ORs a pattern of relative live-cell coordinates into the grid at the
+    /// given origin. Cells falling outside the grid are ignored.
+    pub fn stamp(&mut self, pattern: &[(i32, i32)], origin_x: i32, origin_y: i32) {
+        for &(dx, dy) in pattern {
+            let x = origin_x + dx;
+            let y = origin_y + dy;
+            if (0..self.width as i32).contains(&x) && (0..self.height as i32).contains(&y) {
+                self.set_cell(x as u32, y as u32, true);
+            }
+        }
+    }
+
+    pub fn update(&mut self) {
+        if self.history.len() == HISTORY_DEPTH {
+            self.history.pop_front();
+        }
+        self.history.push_back(Snapshot {
+            cells: self.cells.clone(),
+            ages: self.ages.clone(),
+            decay: self.decay.clone(),
+            population: self.population,
+            population_delta: self.population_delta,
+            period: self.period,
+        });
+
+        std::mem::swap(&mut self.prev_prev_cells, &mut self.prev_cells);
+        self.prev_cells.clone_from(&self.cells);
+
+        // Seed the inherited colors the first time rainbow mode needs
+        // them, or again after the grid changed size underneath them.
+        if self.rainbow && self.colors.len() != self.cells.len() {
+            self.colors = (0..self.cells.len()).map(index_color).collect();
+        }
+
+        // Rebuild the per-cell rule lookup after the regions or the
+        // grid changed underneath it.
+        if !self.rule_regions.is_empty() && self.rule_map.len() != self.cells.len() {
+            self.rebuild_rule_map();
+        }
+
+        let prev_population = self.population;
+        if self.single_buffer {
+            self.update_single_buffer();
+        } else {
+            self.update_double_buffer();
+        }
+        self.population_delta = self.population as i64 - prev_population as i64;
+        self.period = if self.cells == self.prev_cells {
+            Some(1)
+        } else if self.generation >= 1 && self.cells == self.prev_prev_cells {
+            Some(2)
+        } else {
+            None
+        };
+        self.generation += 1;
+
+        if self.grow_limit.is_some() {
+            self.grow_if_needed();
+        }
+    }
+
+    /// Per-phase timings accumulated so far; totals only grow while
+    /// [`profile`](Self::profile) is set.
+    pub fn profile_timings(&self) -> UpdateTimings {
+        self.timings
+    }
+
+    /// The correct update: all neighbour counts are computed from the
+    /// current generation before any cell changes state.
+    fn update_double_buffer(&mut self) {
+        self.rebuild_offsets_if_stale();
+        let dirty = self.dirty_tiles();
+        let width = self.width as usize;
+        let tiles_x = self.width.div_ceil(TILE_SIZE) as usize;
+        let tile_of = move |i: usize| {
+            (i / width / TILE_SIZE as usize) * tiles_x + (i % width) / TILE_SIZE as usize
+        };
+        let mut neighbours = std::mem::take(&mut self.neighbours);
+        neighbours.clear();
+        neighbours.resize(self.cells.len(), 0);
+        let offsets = &self.offsets;
+        let cells = &self.cells;
+        let phase_start = self.profile.then(web_time::Instant::now);
+        neighbours
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(i, num_neighbours)| {
+                if !dirty[tile_of(i)] {
+                    return;
+                }
+                let run = offsets.starts[i] as usize..offsets.starts[i + 1] as usize;
+                *num_neighbours = offsets.indices[run]
+                    .iter()
+                    .filter(|&&j| cells.get(j as usize))
+                    .count() as u8;
+            });
+        if let Some(start) = phase_start {
+            self.timings.neighbours += start.elapsed();
+        }
+
+        let phase_start = self.profile.then(web_time::Instant::now);
+        let mut tile_changed = vec![false; dirty.len()];
+        for (i, num_neighbours) in neighbours.iter().copied().enumerate() {
+            let tile = tile_of(i);
+            if !dirty[tile] {
+                // Quiescent tile: the cells keep their state, but the
+                // survivors in it still grow older.
+                if self.cells.get(i) && !self.frozen.get(i) {
+                    self.ages[i] = self.ages[i].saturating_add(1).min(AGE_CAP);
+                }
+                continue;
+            }
+            if self.apply_rule(i, num_neighbours) {
+                tile_changed[tile] = true;
+            }
+        }
+        self.tile_changed = tile_changed;
+        self.tiles_stale = false;
+        self.neighbours = neighbours;
+        if let Some(start) = phase_start {
+            self.timings.rule += start.elapsed();
+            self.timings.updates += 1;
+        }
+    }
+
+    /// The deliberately-wrong teaching update: each cell is rewritten in
+    /// place, so cells earlier in the scan feed their *new* state into the
+    /// neighbour counts of cells after them.
+    fn update_single_buffer(&mut self) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let num_neighbours = self.neighbours(x, y);
+                self.apply_rule((y * self.width + x) as usize, num_neighbours);
+            }
+        }
+        // The tile change map assumes buffered semantics; recompute
+        // everything next generation.
+        self.tiles_stale = true;
+    }
+
+    /// Applies the rule to one cell given its neighbour count, maintaining
+    /// ages, decay stages, and the population counter. Returns whether the
+    /// cell changed, counting decay steps, for the tile change map.
+    fn apply_rule(&mut self, i: usize, num_neighbours: u8) -> bool {
+        if self.frozen.get(i) {
+            return false;
+        }
+        let rule = self.cell_rule(i);
+        let was_alive = self.cells.get(i);
+        let had_decay = self.decay[i] > 0;
+        let alive = if was_alive {
+            rule.survives(num_neighbours)
+        } else {
+            // Dying cells step through their remaining decay stages and
+            // cannot be born until fully dead.
+            self.decay[i] == 0 && rule.born(num_neighbours)
+        };
+        if was_alive && !alive {
+            self.decay[i] = rule.states - 2;
+        } else if !was_alive {
+            self.decay[i] = self.decay[i].saturating_sub(1);
+        }
+        if self.rainbow && alive && !was_alive {
+            let color = self.birth_color(i);
+            if let Some(slot) = self.colors.get_mut(i) {
+                *slot = color;
+            }
+        }
+        self.ages[i] = if alive {
+            if was_alive {
+                self.ages[i].saturating_add(1).min(AGE_CAP)
+            } else {
+                1
+            }
+        } else {
+            0
+        };
+        self.population = self.population - was_alive as usize + alive as usize;
+        self.cells.set(i, alive);
+        was_alive != alive || had_decay
+    }
+
+    /// The rule governing cell `i`: the default, unless a rule region
+    /// covers the cell.
+    fn cell_rule(&self, i: usize) -> Rule {
+        match self.rule_map.get(i) {
+            Some(&index) if index > 0 => self.rule_regions[index as usize - 1].rule,
+            _ => self.rule,
+        }
+    }
+
+    /// Recomputes the per-cell rule lookup from the region list,
+    /// stamping each region's index over the cells it covers.
+    fn rebuild_rule_map(&mut self) {
+        self.rule_map = vec![0; self.cells.len()];
+        for (index, region) in self.rule_regions.iter().enumerate() {
+            let right = region.x.saturating_add(region.width).min(self.width);
+            let bottom = region.y.saturating_add(region.height).min(self.height);
+            for y in region.y.min(self.height)..bottom {
+                for x in region.x..right {
+                    self.rule_map[(y * self.width + x) as usize] = index as u8 + 1;
+                }
+            }
+        }
+    }
+
+    /// The color a newborn at `i` inherits: the channel-wise average of
+    /// the neighbours that were alive last generation. Cells created
+    /// without parents, by noise or manual edits, keep their seeded hue.
+    fn birth_color(&self, i: usize) -> [u8; 4] {
+        let x = (i % self.width as usize) as isize;
+        let y = (i / self.width as usize) as isize;
+        let radius = self.neighbourhood.radius();
+        let mut sum = [0u32; 3];
+        let mut parents = 0u32;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if (dx == 0 && dy == 0) || !self.neighbourhood.contains(dx, dy, y % 2 == 1) {
+                    continue;
+                }
+                let Some(j) = self.resolve_neighbour(x, y, dx, dy) else {
+                    continue;
+                };
+                if !self.prev_cells.get(j) {
+                    continue;
+                }
+                if let Some(color) = self.colors.get(j) {
+                    for (sum, &channel) in sum.iter_mut().zip(color.iter()) {
+                        *sum += channel as u32;
+                    }
+                    parents += 1;
+                }
+            }
+        }
+        if parents == 0 {
+            return self.colors.get(i).copied().unwrap_or([0xff; 4]);
+        }
+        [
+            (sum[0] / parents) as u8,
+            (sum[1] / parents) as u8,
+            (sum[2] / parents) as u8,
+            0xff,
+        ]
+    }
+
+    /// Flags for which tiles `update` must recompute: a tile is dirty when
+    /// it or any adjacent tile changed last generation, since only then can
+    /// a neighbour count inside it differ. Falls back to recomputing
+    /// everything after out-of-band edits or when the change map does not
+    /// match the grid.
+    fn dirty_tiles(&self) -> Vec<bool> {
+        let tiles_x = self.width.div_ceil(TILE_SIZE) as i64;
+        let tiles_y = self.height.div_ceil(TILE_SIZE) as i64;
+        let num_tiles = (tiles_x * tiles_y) as usize;
+        if self.tiles_stale
+            || self.tile_changed.len() != num_tiles
+            || self.neighbourhood.radius() > TILE_SIZE as isize
+        {
+            return vec![true; num_tiles];
+        }
+
+        let mut dirty = vec![false; num_tiles];
+        for (t, flag) in dirty.iter_mut().enumerate() {
+            let tx = t as i64 % tiles_x;
+            let ty = t as i64 / tiles_x;
+            *flag = (-1..=1).any(|dy| {
+                (-1..=1).any(|dx| {
+                    let mut nx = tx + dx;
+                    let mut ny = ty + dy;
+                    // Tile adjacency crosses the seam on a torus, just as
+                    // the cells underneath do. Mirrored edges reflect back
+                    // into the boundary tile itself, which is already its
+                    // own neighbour.
+                    if self.edge_mode == EdgeMode::Wrap {
+                        nx = nx.rem_euclid(tiles_x);
+                        ny = ny.rem_euclid(tiles_y);
+                    }
+                    (0..tiles_x).contains(&nx)
+                        && (0..tiles_y).contains(&ny)
+                        && self.tile_changed[(ny * tiles_x + nx) as usize]
+                })
+            });
+        }
+        dirty
+    }
+
+    /// Expands the grid by [`GROW_MARGIN`] on every side where live cells
+    /// have reached the boundary, keeping within the configured limit. The
+    /// existing state is re-laid out into the larger grid and the undo
+    /// history, which holds the old dimensions, is dropped.
+    fn grow_if_needed(&mut self) {
+        let Some((max_width, max_height)) = self.grow_limit else {
+            return;
+        };
+        let column_live = |x: u32| (0..self.height).any(|y| self.get(x, y));
+        let row_live = |y: u32| (0..self.width).any(|x| self.get(x, y));
+
+        let room_x = max_width.saturating_sub(self.width);
+        let room_y = max_height.saturating_sub(self.height);
+        let left = if column_live(0) { GROW_MARGIN.min(room_x) } else { 0 };
+        let right = if column_live(self.width - 1) {
+            GROW_MARGIN.min(room_x - left)
+        } else {
+            0
+        };
+        let top = if row_live(0) { GROW_MARGIN.min(room_y) } else { 0 };
+        let bottom = if row_live(self.height - 1) {
+            GROW_MARGIN.min(room_y - top)
+        } else {
+            0
+        };
+        if left + right + top + bottom == 0 {
+            return;
+        }
+
+        let width = self.width + left + right;
+        let height = self.height + top + bottom;
+        let num_cells = (width * height) as usize;
+        let mut cells = BitGrid::new(num_cells);
+        let mut frozen = BitGrid::new(num_cells);
+        let mut ages = vec![0; num_cells];
+        let mut decay = vec![0; num_cells];
+        // Copy every cell's state, not just the live ones: dying cells
+        // under a Generations rule are dead with decay stages left, and
+        // dropping those would make them birth-eligible immediately.
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = ((y + top) * width + x + left) as usize;
+                let old = (y * self.width + x) as usize;
+                cells.set(i, self.cells.get(old));
+                frozen.set(i, self.frozen.get(old));
+                ages[i] = self.ages[old];
+                decay[i] = self.decay[old];
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+        self.frozen = frozen;
+        self.ages = ages;
+        self.decay = decay;
+        self.prev_cells = BitGrid::new(num_cells);
+        self.prev_prev_cells = BitGrid::new(num_cells);
+        self.history.clear();
+        // Keep the view over the same cells now that they have shifted.
+        self.viewport.x += left as i64;
+        self.viewport.y += top as i64;
+    }
+
+    /// Resizes the grid to the given dimensions, clamped like the
+    /// constructor, keeping the cells in the overlapping region at their
+    /// coordinates and filling any new area as dead. The generation
+    /// counter and rule carry over unchanged; the undo history, which
+    /// holds the old dimensions, is dropped. Distinct from the auto-grow
+    /// feature, which expands around live cells on its own.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let (width, height) = clamped_dimensions(width, height);
+        if (width, height) == (self.width, self.height) {
+            return;
+        }
+
+        let num_cells = width as usize * height as usize;
+        let mut cells = BitGrid::new(num_cells);
+        let mut frozen = BitGrid::new(num_cells);
+        let mut ages = vec![0; num_cells];
+        let mut decay = vec![0; num_cells];
+        for y in 0..self.height.min(height) {
+            for x in 0..self.width.min(width) {
+                let i = (y * width + x) as usize;
+                let old = (y * self.width + x) as usize;
+                cells.set(i, self.cells.get(old));
+                frozen.set(i, self.frozen.get(old));
+                ages[i] = self.ages[old];
+                decay[i] = self.decay[old];
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+        self.frozen = frozen;
+        self.ages = ages;
+        self.decay = decay;
+        self.prev_cells = BitGrid::new(num_cells);
+        self.prev_prev_cells = BitGrid::new(num_cells);
+        self.population = (0..num_cells).filter(|&i| self.cells.get(i)).count();
+        self.period = None;
+        self.history.clear();
+        self.tiles_stale = true;
+    }
+
+    /// Steps backwards to the previous generation, if the bounded undo
+    /// history still holds it. Returns whether a step was taken.
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.history.pop_back() else {
+            return false;
+        };
+        self.cells = snapshot.cells;
+        self.ages = snapshot.ages;
+        self.decay = snapshot.decay;
+        self.population = snapshot.population;
+        self.population_delta = snapshot.population_delta;
+        self.period = snapshot.period;
+        self.generation -= 1;
+        self.tiles_stale = true;
+        true
+    }
+
+    /// Reads the cell states of a rectangular region in row-major order.
+    /// The region must lie within the grid.
+    pub fn region(&self, x: u32, y: u32, width: u32, height: u32) -> Vec<bool> {
+        assert!(
+            x + width <= self.width && y + height <= self.height,
+            "region out of range"
+        );
+        let mut cells = Vec::with_capacity((width * height) as usize);
+        for cy in y..y + height {
+            for cx in x..x + width {
+                cells.push(self.get(cx, cy));
+            }
+        }
+        cells
+    }
+
+    /// Overwrites a rectangular region with row-major cell states. Cells
+    /// falling outside the grid are clipped.
+    pub fn set_region(&mut self, x: u32, y: u32, width: u32, height: u32, cells: &[bool]) {
+        assert_eq!(
+            (width * height) as usize,
+            cells.len(),
+            "cell layout does not match region dimensions"
+        );
+        for cy in 0..height {
+            for cx in 0..width {
+                self.set_cell(x + cx, y + cy, cells[(cy * width + cx) as usize]);
+            }
+        }
+    }
+
+    /// Mirrors a rectangular region left-to-right in place.
+    pub fn mirror_region_horizontal(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        let mut region = self.region(x, y, width, height);
+        for row in region.chunks_exact_mut(width as usize) {
+            row.reverse();
+        }
+        self.set_region(x, y, width, height, &region);
+    }
+
+    /// Mirrors a rectangular region top-to-bottom in place.
+    pub fn mirror_region_vertical(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        let mut region = self.region(x, y, width, height);
+        let rows: Vec<&[bool]> = region.chunks_exact(width as usize).rev().collect();
+        let flipped: Vec<bool> = rows.concat();
+        region.copy_from_slice(&flipped);
+        self.set_region(x, y, width, height, &region);
+    }
+
+    /// Rotates a rectangular region 90° clockwise about its top-left
+    /// corner. The rotated region has swapped dimensions and is clipped
+    /// against the grid.
+    pub fn rotate_region_clockwise(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        let region = self.region(x, y, width, height);
+        self.set_region(x, y, width, height, &vec![false; region.len()]);
+
+        let mut rotated = vec![false; region.len()];
+        for cy in 0..height {
+            for cx in 0..width {
+                let dest = (cx * height + (height - 1 - cy)) as usize;
+                rotated[dest] = region[(cy * width + cx) as usize];
+            }
+        }
+        self.set_region(x, y, height, width, &rotated);
+    }
+
+    /// Steps backwards up to `generations` times, stopping early when the
+    /// undo history runs out. Returns the number of steps taken.
+    pub fn rewind(&mut self, generations: usize) -> usize {
+        (0..generations).take_while(|_| self.undo()).count()
+    }
+
+    /// Counts the live neighbours of a cell under the current edge mode
+    /// and neighbourhood. This is the reference for the precomputed index
+    /// table that `update` sums over, exposed so tests and alternative
+    /// update strategies can query single cells directly.
+    pub fn neighbours(&self, x: u32, y: u32) -> u8 {
+        let x = x as isize;
+        let y = y as isize;
+        let radius = self.neighbourhood.radius();
+        let mut count = 0;
+
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if (dx == 0 && dy == 0) || !self.neighbourhood.contains(dx, dy, y % 2 == 1) {
+                    continue;
+                }
+                if let Some(j) = self.resolve_neighbour(x, y, dx, dy) {
+                    count += self.cells.get(j) as u8;
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Resolves the grid index of the neighbour at `(x + dx, y + dy)`
+    /// under the current edge mode, or `None` if it falls outside.
+    fn resolve_neighbour(&self, x: isize, y: isize, dx: isize, dy: isize) -> Option<usize> {
+        let w = self.width as isize;
+        let h = self.height as isize;
+        let mut nx = x + dx;
+        let mut ny = y + dy;
+        match self.edge_mode {
+            EdgeMode::Wrap => {
+                nx = nx.rem_euclid(w);
+                ny = ny.rem_euclid(h);
+            }
+            EdgeMode::Mirror => {
+                if nx < 0 {
+                    nx = -nx - 1;
+                } else if nx >= w {
+                    nx = 2 * w - 1 - nx;
+                }
+                if ny < 0 {
+                    ny = -ny - 1;
+                } else if ny >= h {
+                    ny = 2 * h - 1 - ny;
+                }
+                // A radius larger than the grid can still reflect out of
+                // range; those neighbours count as dead.
+                if nx < 0 || nx >= w || ny < 0 || ny >= h {
+                    return None;
+                }
+            }
+            EdgeMode::Dead => {
+                if nx < 0 || nx >= w || ny < 0 || ny >= h {
+                    return None;
+                }
+            }
+        }
+        Some((ny * w + nx) as usize)
+    }
+
+    /// Rebuilds the neighbour index table if the geometry it was built
+    /// for no longer matches the world.
+    fn rebuild_offsets_if_stale(&mut self) {
+        let key = (self.width, self.height, self.edge_mode, self.neighbourhood);
+        if self.offsets.key == Some(key) {
+            return;
+        }
+
+        let radius = self.neighbourhood.radius();
+        let mut starts = Vec::with_capacity(self.cells.len() + 1);
+        let mut indices = Vec::new();
+        starts.push(0);
+        for i in 0..self.cells.len() {
+            let x = (i % self.width as usize) as isize;
+            let y = (i / self.width as usize) as isize;
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    if (dx == 0 && dy == 0) || !self.neighbourhood.contains(dx, dy, y % 2 == 1) {
+                        continue;
+                    }
+                    if let Some(j) = self.resolve_neighbour(x, y, dx, dy) {
+                        indices.push(j as u32);
+                    }
+                }
+            }
+            starts.push(indices.len() as u32);
+        }
+        self.offsets = OffsetTable {
+            key: Some(key),
+            starts,
+            indices,
+        };
+    }
+
+    /// Renders the world into an RGBA frame that is `frame_width` pixels
+    /// wide, showing the region selected by the viewport; the number of
+    /// rows follows from the buffer length. Pixels that fall outside the
+    /// world are filled with a darkened background, behind a one-pixel
+    /// gray border around the active region.
+    pub fn draw(&self, frame: &mut [u8], frame_width: u32) {
+        let scale_x = self.viewport.scale_x.max(1);
+        let scale_y = self.viewport.scale_y.max(1);
+        let dead = self.background_color();
+        // Half-brightness background for pixels past the world's edge,
+        // so the finite extent stays visible after window resizes. On a
+        // torus the board tiles the plane and has no outside.
+        let outside = {
+            let mut rgba = dead;
+            for channel in rgba.iter_mut().take(3) {
+                *channel /= 2;
+            }
+            rgba
+        };
+        let border = [0x80, 0x80, 0x80, 0xff];
+        // The active region's extent in frame pixels, for the border.
+        let left = -self.viewport.x * scale_x as i64;
+        let right = (self.width as i64 - self.viewport.x) * scale_x as i64;
+        let top = -self.viewport.y * scale_y as i64;
+        let bottom = (self.height as i64 - self.viewport.y) * scale_y as i64;
+        let hex = self.neighbourhood == Neighbourhood::Hex;
+        for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
+            let x = (i % frame_width as usize) as u32;
+            let y = (i / frame_width as usize) as u32;
+            let mut cell_y = self.viewport.y + (y / scale_y) as i64;
+            // On a torus the board tiles the plane, so a panned viewport
+            // shows the wrapped continuation instead of clipping at the
+            // seam, matching the update's topology.
+            if self.edge_mode == EdgeMode::Wrap {
+                cell_y = cell_y.rem_euclid(self.height as i64);
+            }
+            // Draw odd rows half a cell to the right under the hex
+            // neighbourhood, matching the "odd-r" layout it counts over.
+            let hex_shift = if hex && cell_y.rem_euclid(2) == 1 {
+                (scale_x / 2) as i64
+            } else {
+                0
+            };
+            let mut cell_x =
+                self.viewport.x + (x as i64 - hex_shift).div_euclid(scale_x as i64);
+            if self.edge_mode == EdgeMode::Wrap {
+                cell_x = cell_x.rem_euclid(self.width as i64);
+            }
+            let in_world = (0..self.width as i64).contains(&cell_x)
+                && (0..self.height as i64).contains(&cell_y);
+            let j = (cell_y * self.width as i64 + cell_x) as usize;
+            // Indexed defensively: if the parallel per-cell buffers ever
+            // disagree with the grid, render a default rather than panic.
+            let decay = self.decay.get(j).copied().unwrap_or(0);
+            let mut rgba = if in_world && self.cells.get(j) {
+                if self.rainbow {
+                    self.colors.get(j).copied().unwrap_or([0xff; 4])
+                } else {
+                    age_color(self.ages.get(j).copied().unwrap_or(1), &self.palette)
+                }
+            } else if in_world && decay > 0 {
+                decay_color(decay, self.rule.states, &self.palette)
+            } else if in_world {
+                dead
+            } else if ((left - 1)..=right).contains(&(x as i64))
+                && ((top - 1)..=bottom).contains(&(y as i64))
+            {
+                border
+            } else {
+                outside
+            };
+
+            // Blend frozen walls towards gray so they stand out from
+            // ordinary cells in any palette.
+            if in_world && self.frozen.get(j) {
+                for channel in rgba.iter_mut().take(3) {
+                    *channel = (*channel as u16 + 0x80) as u8 / 2 + 0x40;
+                }
+            }
+
+            // Darken cell boundaries to make individual cells easier to
+            // tell apart when painting.
+            if self.grid_overlay && (x.is_multiple_of(scale_x) || y.is_multiple_of(scale_y)) {
+                for channel in rgba.iter_mut().take(3) {
+                    *channel = channel.saturating_sub(0x20);
+                }
+            }
+
+            pixel.copy_from_slice(&rgba);
+        }
+    }
+
+    /// The dead-cell color for this frame: the palette's base color, or,
+    /// with the activity pulse on, that color blended up to a quarter of
+    /// the way towards the live color as more cells changed last update.
+    fn background_color(&self) -> [u8; 4] {
+        if !self.activity_pulse {
+            return self.palette.dead;
+        }
+        let t = self.population_delta.unsigned_abs().min(64) as u32;
+        let mut rgba = self.palette.dead;
+        for (channel, &alive) in rgba.iter_mut().zip(self.palette.alive.iter()).take(3) {
+            *channel = ((*channel as u32 * (256 - t) + alive as u32 * t) / 256) as u8;
+        }
+        rgba
+    }
+}
+
+impl Simulation for World {
+    fn update(&mut self) {
+        World::update(self);
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn population(&self) -> usize {
+        self.population
+    }
+
+    fn get(&self, x: i64, y: i64) -> bool {
+        (0..self.width as i64).contains(&x)
+            && (0..self.height as i64).contains(&y)
+            && World::get(self, x as u32, y as u32)
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, alive: bool) {
+        if (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y) {
+            World::set_cell(self, x as u32, y as u32, alive);
+        }
+    }
+}
+
+/// Blends the live-cell color from the palette's fresh-birth shade
+/// towards its old-age shade as the cell ages, saturating at [`AGE_CAP`].
+fn age_color(age: u8, palette: &Palette) -> [u8; 4] {
+    let t = (age.clamp(1, AGE_CAP) - 1) as u32;
+    let cap = (AGE_CAP - 1) as u32;
+    let mut rgba = [0xff; 4];
+    for (out, (&young, &old)) in rgba
+        .iter_mut()
+        .zip(palette.alive.iter().zip(palette.old.iter()))
+    {
+        *out = ((young as u32 * (cap - t) + old as u32 * t) / cap) as u8;
+    }
+    rgba
+}
+
+/// Brings values a hand-built [`Rule`] could hold that [`Rule::parse`]
+/// would reject into range: neighbour counts above 8 are dropped and a
+/// state count below 2 is raised to 2, with a warning.
+fn sanitized_rule(rule: Rule) -> Rule {
+    let sanitized = Rule {
+        birth: rule.birth & 0x1ff,
+        survival: rule.survival & 0x1ff,
+        states: rule.states.max(2),
+    };
+    if sanitized != rule {
+        log::warn!("rule {rule:?} sanitized to {sanitized:?}");
+    }
+    sanitized
+}
+
+/// The seed color for cell `i` in rainbow mode: a fully saturated hue,
+/// stepped around the color wheel by the golden ratio per index so that
+/// nearby cells start visibly different.
+fn index_color(i: usize) -> [u8; 4] {
+    let hue = (i as f32 * 0.618_034).fract() * 6.0;
+    let ramp = (255.0 * (1.0 - ((hue % 2.0) - 1.0).abs())) as u8;
+    match hue as u32 % 6 {
+        0 => [0xff, ramp, 0x00, 0xff],
+        1 => [ramp, 0xff, 0x00, 0xff],
+        2 => [0x00, 0xff, ramp, 0xff],
+        3 => [0x00, ramp, 0xff, 0xff],
+        4 => [ramp, 0x00, 0xff, 0xff],
+        _ => [0xff, 0x00, ramp, 0xff],
+    }
+}
+
+/// Fades a dying cell from the palette's old-age shade towards the
+/// dead-cell background as it steps through its remaining decay stages.
+fn decay_color(remaining: u8, num_states: u8, palette: &Palette) -> [u8; 4] {
+    let total = (num_states.max(3) - 2) as u32;
+    let remaining = (remaining as u32).min(total);
+    let mut rgba = [0xff; 4];
+    for (out, (&fresh, &dead)) in rgba
+        .iter_mut()
+        .zip(palette.old.iter().zip(palette.dead.iter()))
+    {
+        *out = ((fresh as u32 * remaining + dead as u32 * (total - remaining)) / total) as u8;
+    }
+    rgba
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell_states(world: &World) -> Vec<bool> {
+        (0..world.cells.len()).map(|i| world.cells.get(i)).collect()
+    }
+
+    #[rustfmt::skip]
+    const BLINKER_HORIZONTAL: [bool; 25] = [
+        false, false, false, false, false,
+        false, false, false, false, false,
+        false, true,  true,  true,  false,
+        false, false, false, false, false,
+        false, false, false, false, false,
+    ];
+
+    #[rustfmt::skip]
+    const BLINKER_VERTICAL: [bool; 25] = [
+        false, false, false, false, false,
+        false, false, true,  false, false,
+        false, false, true,  false, false,
+        false, false, true,  false, false,
+        false, false, false, false, false,
+    ];
+
+    #[test]
+    fn parse_conway_rule() {
+        assert_eq!(Rule::parse("B3/S23"), Ok(Rule::CONWAY));
+    }
+
+    #[test]
+    fn parse_highlife_rule() {
+        let rule = Rule::parse("B36/S23").unwrap();
+        assert!(rule.born(3) && rule.born(6) && !rule.born(2));
+        assert!(rule.survives(2) && rule.survives(3) && !rule.survives(6));
+    }
+
+    #[test]
+    fn parse_empty_survival() {
+        let rule = Rule::parse("B2/S").unwrap();
+        assert_eq!(rule.survival, 0);
+    }
+
+    #[test]
+    fn parse_variant_notations() {
+        assert_eq!(Rule::parse("23/3"), Ok(Rule::CONWAY));
+        assert_eq!(Rule::parse("S23/B3"), Ok(Rule::CONWAY));
+        assert_eq!(Rule::parse("s23/b3"), Ok(Rule::CONWAY));
+        assert_eq!(Rule::parse("B3/23"), Err(RuleParseError::MixedNotation));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_rules() {
+        assert_eq!(Rule::parse("B3S23"), Err(RuleParseError::MissingSurvival));
+        assert_eq!(Rule::parse("B3"), Err(RuleParseError::MissingSurvival));
+        assert_eq!(Rule::parse("B9/S23"), Err(RuleParseError::DigitOutOfRange('9')));
+        assert_eq!(Rule::parse("Bx/S23"), Err(RuleParseError::UnexpectedChar('x')));
+    }
+
+    #[test]
+    fn brians_brain_constant_matches_its_notation() {
+        assert_eq!(Rule::parse("B2/S/C3"), Ok(Rule::BRIANS_BRAIN));
+    }
+
+    #[test]
+    fn parse_generations_state_count() {
+        assert_eq!(Rule::parse("B2/S/C3").unwrap().states, 3);
+        assert_eq!(Rule::parse("B2/S/3").unwrap().states, 3);
+        assert_eq!(Rule::parse("B3/S23").unwrap().states, 2);
+        assert!(matches!(
+            Rule::parse("B3/S23/1"),
+            Err(RuleParseError::InvalidStates(_))
+        ));
+        assert!(matches!(
+            Rule::parse("B3/S23/x"),
+            Err(RuleParseError::InvalidStates(_))
+        ));
+    }
+
+    #[test]
+    fn seeded_fills_are_reproducible() {
+        let mut rng_a = fastrand::Rng::with_seed(42);
+        let mut rng_b = fastrand::Rng::with_seed(42);
+        let world_a = World::new(16, 16, 0.5, EdgeMode::Dead, &mut rng_a);
+        let world_b = World::new(16, 16, 0.5, EdgeMode::Dead, &mut rng_b);
+        assert_eq!(cell_states(&world_a), cell_states(&world_b));
+    }
+
+    #[test]
+    fn new_clamps_zero_dimensions() {
+        let mut rng = fastrand::Rng::with_seed(1);
+        let world = World::new(0, 5, 0.5, EdgeMode::Dead, &mut rng);
+        assert_eq!((world.width, world.height), (1, 5));
+        assert_eq!(world.cells.len(), 5);
+
+        let world = World::new(0, 0, 0.5, EdgeMode::Dead, &mut rng);
+        assert_eq!((world.width, world.height), (1, 1));
+    }
+
+    #[test]
+    fn new_clamps_overflowing_dimensions() {
+        // u32::MAX squared overflows both `u32` and (on 32-bit targets)
+        // `usize`; the constructor halves the request until it fits.
+        let mut rng = fastrand::Rng::with_seed(1);
+        let world = World::new(u32::MAX, u32::MAX, 0.0, EdgeMode::Dead, &mut rng);
+        assert!(world.width as u64 * world.height as u64 <= MAX_CELLS);
+        assert_eq!(
+            world.cells.len(),
+            world.width as usize * world.height as usize
+        );
+    }
+
+    #[test]
+    fn blinker_oscillates() {
+        let mut world = World::from_cells(5, 5, &BLINKER_HORIZONTAL);
+        world.update();
+        assert_eq!(cell_states(&world), BLINKER_VERTICAL);
+        world.update();
+        assert_eq!(cell_states(&world), BLINKER_HORIZONTAL);
+    }
+
+    #[test]
+    fn block_is_still() {
+        #[rustfmt::skip]
+        let block = [
+            false, false, false, false,
+            false, true,  true,  false,
+            false, true,  true,  false,
+            false, false, false, false,
+        ];
+        let mut world = World::from_cells(4, 4, &block);
+        world.update();
+        assert_eq!(cell_states(&world), block);
+    }
+
+    #[test]
+    fn draw_matches_cell_states() {
+        let world = World::from_cells(2, 2, &[true, false, false, true]);
+        let mut frame = [0u8; 2 * 2 * 4];
+        world.draw(&mut frame, 2);
+
+        let alive = [0x5e, 0x48, 0xe8, 0xff];
+        let dead = [0x48, 0xb2, 0xe8, 0xff];
+        assert_eq!(frame[0..4], alive);
+        assert_eq!(frame[4..8], dead);
+        assert_eq!(frame[8..12], dead);
+        assert_eq!(frame[12..16], alive);
+    }
+
+    #[test]
+    fn set_rule_sanitizes_hand_built_rules() {
+        let mut world = World::from_cells(1, 1, &[false]);
+        world.set_rule(Rule {
+            birth: 0xffff,
+            survival: 1 << 3,
+            states: 0,
+        });
+        assert_eq!(
+            world.rule(),
+            Rule {
+                birth: 0x1ff,
+                survival: 1 << 3,
+                states: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn hex_neighbourhood_depends_on_row_parity() {
+        let mut world = World::from_cells(5, 5, &[false; 25]);
+        world.neighbourhood = Neighbourhood::Hex;
+        world.set_cell(1, 1, true);
+
+        // Cells on even rows look up-left and up, odd rows up and
+        // up-right, so (1, 1) is adjacent to (2, 2) but not (3, 2).
+        assert_eq!(world.neighbours(2, 2), 1);
+        assert_eq!(world.neighbours(3, 2), 0);
+        assert_eq!(world.neighbours(1, 0), 1);
+        assert_eq!(world.neighbours(3, 0), 0);
+        assert_eq!(world.neighbours(0, 1), 1);
+
+        // An interior cell of a full board has exactly six neighbours.
+        let mut full = World::from_cells(5, 5, &[true; 25]);
+        full.neighbourhood = Neighbourhood::Hex;
+        assert_eq!(full.neighbours(2, 2), 6);
+    }
+
+    #[test]
+    fn hex_draw_shifts_odd_rows_half_a_cell() {
+        let mut world = World::from_cells(2, 2, &[false, false, true, false]);
+        world.neighbourhood = Neighbourhood::Hex;
+        world.viewport.scale_x = 2;
+        world.viewport.scale_y = 2;
+        let mut frame = [0u8; 4 * 4 * 4];
+        world.draw(&mut frame, 4);
+
+        // The live cell at (0, 1) starts one pixel in on its shifted
+        // row; the first pixel of that row falls just outside the
+        // region and picks up the border instead of the cell.
+        let row = 2 * 4 * 4;
+        assert_eq!(frame[row..row + 4], [0x80, 0x80, 0x80, 0xff]);
+        assert_ne!(frame[row + 4..row + 8], world.palette.dead);
+    }
+
+    #[test]
+    fn maintained_population_matches_a_full_scan() {
+        let scan = |world: &World| world.live_cells().count();
+        let mut rng = fastrand::Rng::with_seed(9);
+        let mut world = World::new(32, 32, 0.4, EdgeMode::Wrap, &mut rng);
+        assert_eq!(world.population(), scan(&world));
+
+        // Exercise every path that adjusts the running count: rule
+        // application, noise, manual edits, and undo.
+        for step in 0..32u32 {
+            world.update();
+            world.apply_noise(0.05, &mut rng);
+            if step.is_multiple_of(7) {
+                world.set_cell(step, step, true);
+                world.set_cell(step + 1, step, false);
+            }
+            debug_assert_eq!(world.population(), scan(&world));
+            assert_eq!(world.population(), scan(&world));
+        }
+
+        world.undo();
+        assert_eq!(world.population(), scan(&world));
+    }
+
+    #[test]
+    fn activity_pulse_tints_the_background_with_the_delta() {
+        let mut world = World::from_cells(1, 1, &[false]);
+        let mut frame = [0u8; 4];
+
+        // Off by default, and on but idle, the base dead color shows.
+        world.draw(&mut frame, 1);
+        assert_eq!(frame, world.palette.dead);
+        world.activity_pulse = true;
+        world.draw(&mut frame, 1);
+        assert_eq!(frame, world.palette.dead);
+
+        // A busy board shifts the background towards the live color.
+        world.population_delta = 64;
+        world.draw(&mut frame, 1);
+        assert_ne!(frame, world.palette.dead);
+        assert_eq!(frame, world.background_color());
+    }
+
+    #[test]
+    fn draw_tolerates_mismatched_frame_sizes() {
+        let world = World::from_cells(4, 4, &[true; 16]);
+
+        // An undersized frame renders as many pixels as it holds.
+        let mut small = [0u8; 2 * 4];
+        world.draw(&mut small, 4);
+        assert_eq!(small[0..4], [0x5e, 0x48, 0xe8, 0xff]);
+
+        // An oversized frame marks the excess as out of bounds: a gray
+        // border one pixel past the region, darkened background beyond.
+        let mut large = [0u8; 8 * 8 * 4];
+        world.draw(&mut large, 8);
+        assert_eq!(large[4 * 4..5 * 4], [0x80, 0x80, 0x80, 0xff]);
+        assert_eq!(large[7 * 4..8 * 4], [0x24, 0x59, 0x74, 0xff]);
+
+        // Out-of-range lookups clip to dead instead of panicking.
+        assert!(!world.get(99, 99));
+        assert!(!world.is_frozen(99, 99));
+    }
+
+    #[test]
+    fn draw_wraps_the_viewport_on_a_torus() {
+        let mut world = World::from_cells(2, 2, &[true, false, false, false]);
+        world.edge_mode = EdgeMode::Wrap;
+        world.viewport.x = -2;
+        world.viewport.y = -2;
+        let mut frame = [0u8; 2 * 2 * 4];
+        world.draw(&mut frame, 2);
+
+        // The viewport shows the tile one period up-left, which on a
+        // torus is identical to the board itself.
+        let alive = [0x5e, 0x48, 0xe8, 0xff];
+        let dead = [0x48, 0xb2, 0xe8, 0xff];
+        assert_eq!(frame[0..4], alive);
+        assert_eq!(frame[4..8], dead);
+
+        // With dead edges the same viewport sits outside the world, so
+        // the out-of-bounds fill shows instead of the board.
+        world.edge_mode = EdgeMode::Dead;
+        world.draw(&mut frame, 2);
+        assert_eq!(frame[0..4], [0x24, 0x59, 0x74, 0xff]);
+        // The pixel diagonally adjacent to the region is its border.
+        assert_eq!(frame[12..16], [0x80, 0x80, 0x80, 0xff]);
+    }
+
+    #[test]
+    fn block_detects_period_one() {
+        #[rustfmt::skip]
+        let block = [
+            false, false, false, false,
+            false, true,  true,  false,
+            false, true,  true,  false,
+            false, false, false, false,
+        ];
+        let mut world = World::from_cells(4, 4, &block);
+        world.update();
+        assert_eq!(world.period, Some(1));
+    }
+
+    #[test]
+    fn blinker_detects_period_two() {
+        let mut world = World::from_cells(5, 5, &BLINKER_HORIZONTAL);
+        world.update();
+        assert_eq!(world.period, None);
+        world.update();
+        assert_eq!(world.period, Some(2));
+    }
+
+    #[test]
+    fn generations_rule_decays_before_dying() {
+        #[rustfmt::skip]
+        let cells = [
+            false, false, false, false,
+            false, true,  true,  false,
+            false, false, false, false,
+            false, false, false, false,
+        ];
+        let mut world = World::from_cells(4, 4, &cells);
+        world.rule = Rule::BRIANS_BRAIN;
+
+        world.update();
+        // The original pair fails to survive and starts dying; the four
+        // cells with exactly two live neighbours are born.
+        assert!(!world.get(1, 1) && !world.get(2, 1));
+        assert_eq!(world.decay[5], 1);
+        assert!(world.get(1, 0) && world.get(2, 0) && world.get(1, 2) && world.get(2, 2));
+        assert_eq!(world.population, 4);
+
+        world.update();
+        // Dying cells finish decaying and are not reborn while dying.
+        assert_eq!(world.decay[5], 0);
+        assert!(!world.get(1, 1) && !world.get(2, 1));
+    }
+
+    #[test]
+    fn edge_modes_resolve_out_of_bounds_neighbours() {
+        #[rustfmt::skip]
+        let cells = [
+            true,  false, false,
+            false, false, false,
+            false, false, false,
+        ];
+        let mut world = World::from_cells(3, 3, &cells);
+        // With a dead border or a torus the lone corner cell sees nothing.
+        assert_eq!(world.neighbours(0, 0), 0);
+        world.edge_mode = EdgeMode::Wrap;
+        assert_eq!(world.neighbours(0, 0), 0);
+        // A mirrored boundary reflects three of its out-of-bounds
+        // neighbours back onto the live corner itself.
+        world.edge_mode = EdgeMode::Mirror;
+        assert_eq!(world.neighbours(0, 0), 3);
+    }
+
+    #[test]
+    fn neighbours_at_corners_edges_and_interior() {
+        let mut world = World::from_cells(3, 3, &[true; 9]);
+        // With a dead border, cells lose the neighbours beyond the edge.
+        assert_eq!(world.neighbours(0, 0), 3);
+        assert_eq!(world.neighbours(1, 0), 5);
+        assert_eq!(world.neighbours(1, 1), 8);
+        // On a torus every cell is an interior cell.
+        world.edge_mode = EdgeMode::Wrap;
+        assert_eq!(world.neighbours(0, 0), 8);
+        assert_eq!(world.neighbours(1, 0), 8);
+        assert_eq!(world.neighbours(1, 1), 8);
+    }
+
+    #[test]
+    fn von_neumann_neighbourhood_ignores_diagonals() {
+        #[rustfmt::skip]
+        let cells = [
+            true,  false, true,
+            false, false, false,
+            true,  false, true,
+        ];
+        let mut world = World::from_cells(3, 3, &cells);
+        world.neighbourhood = Neighbourhood::VonNeumann { radius: 1 };
+        assert_eq!(world.neighbours(1, 1), 0);
+        world.neighbourhood = Neighbourhood::MOORE;
+        assert_eq!(world.neighbours(1, 1), 4);
+    }
+
+    #[test]
+    fn extended_radius_counts_distant_cells() {
+        #[rustfmt::skip]
+        let cells = [
+            true,  false, false, false, true,
+            false, false, false, false, false,
+            false, false, false, false, false,
+            false, false, false, false, false,
+            true,  false, false, false, true,
+        ];
+        let mut world = World::from_cells(5, 5, &cells);
+        assert_eq!(world.neighbours(2, 2), 0);
+        world.neighbourhood = Neighbourhood::Moore { radius: 2 };
+        assert_eq!(world.neighbours(2, 2), 4);
+        world.neighbourhood = Neighbourhood::VonNeumann { radius: 2 };
+        assert_eq!(world.neighbours(2, 2), 0);
+    }
+
+    #[test]
+    fn stamp_ignores_out_of_range_cells() {
+        let mut world = World::from_cells(3, 3, &[false; 9]);
+        world.stamp(patterns::GLIDER, 1, 1);
+        // Only the glider cell inside the 3x3 grid is set.
+        assert_eq!(cell_states(&world).iter().filter(|&&a| a).count(), 1);
+        assert!(world.get(2, 1));
+    }
+
+    #[test]
+    fn gosper_gun_emits_a_glider() {
+        let mut world = World::from_cells(50, 40, &[false; 50 * 40]);
+        world.stamp(patterns::GOSPER_GLIDER_GUN, 1, 1);
+        let initial_population = cell_states(&world).iter().filter(|&&a| a).count();
+        assert_eq!(initial_population, 36);
+
+        // After one full gun period the population has grown by one glider.
+        for _ in 0..30 {
+            world.update();
+        }
+        let population = cell_states(&world).iter().filter(|&&a| a).count();
+        assert_eq!(population, initial_population + 5);
+    }
+
+    #[test]
+    fn live_cells_yields_row_major_coordinates() {
+        #[rustfmt::skip]
+        let cells = [
+            false, true,  false,
+            false, false, true,
+            true,  false, false,
+        ];
+        let world = World::from_cells(3, 3, &cells);
+        let live: Vec<(u32, u32)> = world.live_cells().collect();
+        assert_eq!(live, [(1, 0), (2, 1), (0, 2)]);
+        assert_eq!(World::from_cells(2, 2, &[false; 4]).live_cells().count(), 0);
+    }
+
+    #[test]
+    fn live_bounds_covers_the_population() {
+        #[rustfmt::skip]
+        let cells = [
+            false, false, false, false,
+            false, true,  false, false,
+            false, false, true,  false,
+            false, false, false, false,
+        ];
+        let world = World::from_cells(4, 4, &cells);
+        assert_eq!(world.live_bounds(), Some((1, 1, 2, 2)));
+        assert_eq!(World::from_cells(2, 2, &[false; 4]).live_bounds(), None);
+    }
+
+    #[test]
+    fn frozen_cells_never_change_state() {
+        #[rustfmt::skip]
+        let cells = [
+            false, true,  false,
+            false, true,  false,
+            false, true,  false,
+        ];
+        let mut world = World::from_cells(3, 3, &cells);
+        // Freeze the blinker's center alive and a corner dead.
+        world.set_frozen(1, 1, true);
+        world.set_frozen(0, 0, true);
+
+        world.update();
+        // The frozen live cell still counted as a neighbour, so the
+        // blinker flipped as usual around it.
+        assert!(world.get(0, 1) && world.get(1, 1) && world.get(2, 1));
+        assert!(!world.get(0, 0));
+
+        world.clear();
+        assert!(world.get(1, 1), "clear must not remove frozen cells");
+        assert_eq!(world.population, 1);
+    }
+
+    #[test]
+    fn tile_skipping_does_not_change_the_simulation() {
+        // A glider crossing many quiescent tiles must evolve exactly as
+        // on a fully recomputed board.
+        let mut world = World::from_cells(64, 64, &vec![false; 64 * 64]);
+        world.stamp(patterns::GLIDER, 0, 0);
+        for _ in 0..160 {
+            world.update();
+        }
+        assert_eq!(world.population, 5);
+        let live: Vec<(u32, u32)> = world.live_cells().collect();
+        let expected: Vec<(u32, u32)> = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+            .iter()
+            .map(|&(x, y)| (x + 40, y + 40))
+            .collect();
+        assert_eq!(live, expected);
+    }
+
+    #[test]
+    fn edits_in_quiescent_tiles_wake_them_up() {
+        // Let a block settle so every tile is quiescent, then grow it by
+        // hand into a pre-blinker; the next update must notice the edit.
+        let mut world = World::from_cells(48, 48, &vec![false; 48 * 48]);
+        world.set_region(20, 20, 2, 2, &[true; 4]);
+        for _ in 0..4 {
+            world.update();
+        }
+        assert_eq!(world.period, Some(1));
+
+        world.set_cell(22, 20, true);
+        world.set_cell(22, 21, true);
+        world.update();
+        assert_eq!(world.population, 6, "the edited region must evolve");
+    }
+
+    #[test]
+    fn single_buffer_mode_shows_the_classic_bug() {
+        let mut buffered = World::from_cells(5, 5, &BLINKER_HORIZONTAL);
+        let mut in_place = buffered.clone();
+        in_place.single_buffer = true;
+
+        buffered.update();
+        in_place.update();
+        assert_ne!(
+            cell_states(&buffered),
+            cell_states(&in_place),
+            "updating in place must corrupt the blinker"
+        );
+
+        // Switching back restores correct double-buffered behavior.
+        in_place.single_buffer = false;
+        let before = cell_states(&in_place);
+        let mut reference = World::from_cells(5, 5, &before);
+        in_place.update();
+        reference.update();
+        assert_eq!(cell_states(&in_place), cell_states(&reference));
+    }
+
+    #[test]
+    fn gradient_fills_shape_the_density() {
+        let mut world = World::from_cells(40, 40, &[false; 1600]);
+        let mut rng = fastrand::Rng::with_seed(7);
+
+        world.randomize_with(1.0, FillMode::Horizontal, &mut rng);
+        let left = world.live_cells().filter(|&(x, _)| x < 20).count();
+        let right = world.population - left;
+        assert!(left > right, "horizontal fill must be densest on the left");
+
+        world.randomize_with(1.0, FillMode::Radial, &mut rng);
+        let central = world
+            .live_cells()
+            .filter(|&(x, y)| (10..30).contains(&x) && (10..30).contains(&y))
+            .count();
+        let outer = world.population - central;
+        let central_density = central as f64 / 400.0;
+        let outer_density = outer as f64 / 1200.0;
+        assert!(
+            central_density > outer_density,
+            "radial fill must be densest at the center"
+        );
+    }
+
+    #[test]
+    fn noise_flips_cells_but_respects_frozen_walls() {
+        let mut world = World::from_cells(3, 3, &[false; 9]);
+        world.set_frozen(0, 0, true);
+        let mut rng = fastrand::Rng::with_seed(1);
+
+        world.apply_noise(0.0, &mut rng);
+        assert_eq!(world.population, 0, "zero noise must be a no-op");
+
+        // At probability one every non-frozen cell flips.
+        world.apply_noise(1.0, &mut rng);
+        assert_eq!(world.population, 8);
+        assert!(!world.get(0, 0));
+        world.apply_noise(1.0, &mut rng);
+        assert_eq!(world.population, 0);
+    }
+
+    #[test]
+    fn mirror_region_flips_in_place() {
+        #[rustfmt::skip]
+        let cells = [
+            true,  false, false,
+            true,  false, false,
+            false, false, false,
+        ];
+        let mut world = World::from_cells(3, 3, &cells);
+        world.mirror_region_horizontal(0, 0, 3, 2);
+        #[rustfmt::skip]
+        let expected = [
+            false, false, true,
+            false, false, true,
+            false, false, false,
+        ];
+        assert_eq!(cell_states(&world), expected);
+
+        world.mirror_region_vertical(0, 0, 3, 3);
+        #[rustfmt::skip]
+        let expected = [
+            false, false, false,
+            false, false, true,
+            false, false, true,
+        ];
+        assert_eq!(cell_states(&world), expected);
+    }
+
+    #[test]
+    fn rotate_region_turns_clockwise() {
+        #[rustfmt::skip]
+        let cells = [
+            true,  true,  true,
+            false, false, false,
+            false, false, false,
+        ];
+        let mut world = World::from_cells(3, 3, &cells);
+        world.rotate_region_clockwise(0, 0, 3, 3);
+        #[rustfmt::skip]
+        let expected = [
+            false, false, true,
+            false, false, true,
+            false, false, true,
+        ];
+        assert_eq!(cell_states(&world), expected);
+    }
+
+    #[test]
+    fn world_grows_when_cells_reach_the_edge() {
+        let mut world = World::from_cells(8, 8, &[false; 64]);
+        world.grow_limit = Some((32, 32));
+        world.stamp(patterns::GLIDER, 4, 4);
+
+        for _ in 0..8 {
+            world.update();
+        }
+        assert_eq!((world.width, world.height), (24, 24));
+        assert_eq!(world.population, 5);
+    }
+
+    #[test]
+    fn growing_preserves_decay_stages() {
+        // A cell dying under a Generations rule at the moment the grid
+        // grows must keep its remaining decay stages in the new layout.
+        let mut world = World::from_cells(4, 4, &[false; 16]);
+        world.set_rule(Rule::BRIANS_BRAIN);
+        world.grow_limit = Some((32, 32));
+        world.set_cell(0, 1, true);
+        world.set_cell(1, 1, true);
+        world.update();
+
+        assert!(world.width > 4);
+        let decaying = world.decay.iter().filter(|&&stages| stages > 0).count();
+        assert_eq!(decaying, 2);
+    }
+
+    #[test]
+    fn world_growth_respects_the_limit() {
+        let mut world = World::from_cells(8, 8, &[false; 64]);
+        world.grow_limit = Some((10, 10));
+        world.stamp(patterns::GLIDER, 4, 4);
+
+        for _ in 0..20 {
+            world.update();
+        }
+        assert!(world.width <= 10 && world.height <= 10);
+    }
+
+    #[test]
+    fn undo_restores_the_previous_generation() {
+        let mut world = World::from_cells(5, 5, &BLINKER_HORIZONTAL);
+        world.update();
+        assert_eq!(cell_states(&world), BLINKER_VERTICAL);
+
+        assert!(world.undo());
+        assert_eq!(cell_states(&world), BLINKER_HORIZONTAL);
+        assert_eq!(world.generation, 0);
+        assert_eq!(world.population, 3);
+        assert!(!world.undo());
+    }
+
+    #[test]
+    fn rewind_stops_at_the_start_of_history() {
+        let mut world = World::from_cells(5, 5, &BLINKER_HORIZONTAL);
+        for _ in 0..3 {
+            world.update();
+        }
+        assert_eq!(world.rewind(10), 3);
+        assert_eq!(world.generation, 0);
+        assert_eq!(cell_states(&world), BLINKER_HORIZONTAL);
+    }
+
+    #[test]
+    fn undo_history_is_bounded() {
+        let mut world = World::from_cells(5, 5, &BLINKER_HORIZONTAL);
+        for _ in 0..40 {
+            world.update();
+        }
+        let mut undone = 0;
+        while world.undo() {
+            undone += 1;
+        }
+        assert_eq!(undone, 32);
+        assert_eq!(world.generation, 8);
+    }
+
+    #[test]
+    fn population_tracks_births_and_deaths() {
+        let mut world = World::from_cells(5, 5, &BLINKER_HORIZONTAL);
+        assert_eq!(world.population, 3);
+        world.update();
+        assert_eq!(world.population, 3);
+        assert_eq!(world.population_delta, 0);
+
+        world.set_cell(0, 0, true);
+        assert_eq!(world.population, 4);
+    }
+
+    #[test]
+    fn glider_moves_diagonally() {
+        #[rustfmt::skip]
+        let glider = [
+            false, true,  false, false, false, false,
+            false, false, true,  false, false, false,
+            true,  true,  true,  false, false, false,
+            false, false, false, false, false, false,
+            false, false, false, false, false, false,
+            false, false, false, false, false, false,
+        ];
+        // The glider repeats its shape every four generations, shifted one
+        // cell down and one cell right.
+        #[rustfmt::skip]
+        let translated = [
+            false, false, false, false, false, false,
+            false, false, true,  false, false, false,
+            false, false, false, true,  false, false,
+            false, true,  true,  true,  false, false,
+            false, false, false, false, false, false,
+            false, false, false, false, false, false,
+        ];
+        let mut world = World::from_cells(6, 6, &glider);
+        for _ in 0..4 {
+            world.update();
+        }
+        assert_eq!(cell_states(&world), translated);
+    }
+
+    #[test]
+    fn colliding_gliders_leave_the_well_known_debris() {
+        // A south-east glider and its 180-degree rotation, which heads
+        // back north-west across the same diagonal.
+        let rotated: Vec<(i32, i32)> = patterns::GLIDER
+            .iter()
+            .map(|&(x, y)| (2 - x, 2 - y))
+            .collect();
+
+        // Meeting head-on, the pair annihilates completely.
+        let mut world = World::from_cells(40, 40, &[false; 1600]);
+        world.stamp(patterns::GLIDER, 5, 5);
+        world.stamp(&rotated, 15, 15);
+        for _ in 0..24 {
+            world.update();
+        }
+        assert_eq!(world.population, 0);
+
+        // Offset by half the distance, the collision settles into a
+        // single block instead.
+        let mut world = World::from_cells(40, 40, &[false; 1600]);
+        world.stamp(patterns::GLIDER, 5, 5);
+        world.stamp(&rotated, 10, 10);
+        for _ in 0..30 {
+            world.update();
+        }
+        let block: Vec<(u32, u32)> = world.live_cells().collect();
+        assert_eq!(block, [(8, 8), (9, 8), (8, 9), (9, 9)]);
+    }
+
+    #[test]
+    fn invert_complements_the_board_around_frozen_walls() {
+        let mut world = World::from_cells(2, 2, &[true, false, false, false]);
+        world.set_frozen(1, 1, true);
+        world.invert();
+
+        // Every free cell flips; the frozen corner keeps its state.
+        assert_eq!(cell_states(&world), [false, true, true, false]);
+        assert_eq!(world.population, 2);
+        assert_eq!(world.ages, [0, 1, 1, 0]);
+
+        world.invert();
+        assert_eq!(cell_states(&world), [true, false, false, false]);
+    }
+
+    #[test]
+    fn rainbow_births_average_their_parents_colors() {
+        let mut world = World::from_cells(5, 5, &BLINKER_HORIZONTAL);
+        world.rainbow = true;
+        world.colors = vec![[0, 0, 0, 0xff]; 25];
+        world.colors[11] = [0xff, 0x00, 0x00, 0xff];
+        world.colors[12] = [0x00, 0xff, 0x00, 0xff];
+        world.colors[13] = [0x00, 0x00, 0xff, 0xff];
+        world.update();
+
+        // The cells born above and below the blinker's centre inherit
+        // the average of all three parents; the surviving centre keeps
+        // its own color.
+        let average = [0x55, 0x55, 0x55, 0xff];
+        assert_eq!(world.colors[7], average);
+        assert_eq!(world.colors[17], average);
+        assert_eq!(world.colors[12], [0x00, 0xff, 0x00, 0xff]);
+
+        // Live pixels are drawn with the inherited colors.
+        let mut frame = [0u8; 5 * 5 * 4];
+        world.draw(&mut frame, 5);
+        assert_eq!(frame[7 * 4..8 * 4], average);
+    }
+
+    #[test]
+    fn rule_regions_run_their_own_rule() {
+        // Two identical blinkers; the right one sits in a region whose
+        // rule has no survival counts, so its cells all die while the
+        // births still happen.
+        let mut world = World::from_cells(10, 5, &[false; 50]);
+        world.stamp(&[(1, 2), (2, 2), (3, 2)], 0, 0);
+        world.stamp(&[(6, 2), (7, 2), (8, 2)], 0, 0);
+        world.add_rule_region(RuleRegion {
+            x: 5,
+            y: 0,
+            width: 5,
+            height: 5,
+            rule: Rule {
+                birth: 1 << 3,
+                survival: 0,
+                states: 2,
+            },
+        });
+        world.update();
+
+        let live: Vec<(u32, u32)> = world.live_cells().collect();
+        assert_eq!(live, [(2, 1), (7, 1), (2, 2), (2, 3), (7, 3)]);
+
+        // Dropping the regions restores the default rule everywhere.
+        world.clear_rule_regions();
+        world.update();
+        assert!(world.live_cells().count() > 0);
+        assert!(world.rule_regions().is_empty());
+    }
+
+    #[test]
+    fn resize_keeps_the_overlapping_cells_in_place() {
+        let mut world = World::from_cells(5, 5, &BLINKER_HORIZONTAL);
+        world.update();
+        assert_eq!(world.generation(), 1);
+
+        // Growing keeps every cell at its coordinates and adds dead
+        // space; the blinker still oscillates in the corner.
+        world.resize(8, 7);
+        assert_eq!(world.dimensions(), (8, 7));
+        assert_eq!(world.generation(), 1);
+        let live: Vec<(u32, u32)> = world.live_cells().collect();
+        assert_eq!(live, [(2, 1), (2, 2), (2, 3)]);
+        world.update();
+        let live: Vec<(u32, u32)> = world.live_cells().collect();
+        assert_eq!(live, [(1, 2), (2, 2), (3, 2)]);
+
+        // Shrinking truncates cells past the new edge and keeps the rest.
+        world.resize(2, 5);
+        assert_eq!(world.population(), 1);
+        assert_eq!(world.live_cells().collect::<Vec<_>>(), [(1, 2)]);
+    }
+
+    #[test]
+    fn rule_presets_match_their_notation() {
+        let notations = ["B3/S23", "B36/S23", "B3678/S34678", "B2/S", "B1357/S1357"];
+        for ((name, rule), notation) in Rule::PRESETS.iter().zip(notations) {
+            assert_eq!(*rule, Rule::parse(notation).unwrap(), "{name}");
+        }
+    }
+}
@@ -1,49 +1,684 @@
 #![deny(clippy::all)]
 #![forbid(unsafe_code)]
 
+use clap::Parser;
 use error_iter::ErrorIter as _;
-use fastrand;
+#[cfg(not(target_arch = "wasm32"))]
+use game_of_life_rs::{load_cells_pattern, load_rle_pattern_with_rule, Replay, ReplayRecorder};
+use game_of_life_rs::{patterns, EdgeMode, FillMode, Palette, Rule, RuleRegion, World};
 use log::error;
 use pixels::{Error, Pixels, SurfaceTexture};
-use std::time::SystemTime;
+use std::collections::VecDeque;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::{BufWriter, Write};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, SystemTime};
+use web_time::Instant;
 use winit::dpi::LogicalSize;
 use winit::event::{Event, VirtualKeyCode};
 use winit::event_loop::{ControlFlow, EventLoop};
-use winit::window::WindowBuilder;
+use winit::window::{Fullscreen, WindowBuilder};
 use winit_input_helper::WinitInputHelper;
 
-const WIDTH: u32 = 640;
-const HEIGHT: u32 = 480;
-const SCALE_FACTOR: u32 = 4;
-const FILL_RATE: f32 = 0.1;
+const MIN_UPDATE_INTERVAL: f64 = 0.01;
+/// Generations rewound per undo press while `Shift` is held.
+const REWIND_STEPS: usize = 8;
+/// Largest square brush radius reachable with `]`.
+const MAX_BRUSH_RADIUS: u32 = 8;
+/// Pixel size of one overlay font cell; glyphs are 3x5 of these.
+const OVERLAY_SCALE: u32 = 2;
+/// Upper bound on frames captured into a single GIF recording.
+const MAX_GIF_FRAMES: u32 = 600;
+const MAX_UPDATE_INTERVAL: f64 = 2.0;
+/// Most update steps allowed to pile up before the backlog is dropped,
+/// so a long rendering stall cannot snowball into a catch-up spiral.
+const MAX_UPDATE_BACKLOG: f64 = 4.0;
+const UPDATE_INTERVAL_FACTOR: f64 = 1.5;
+/// Generations a soup may run before it is declared non-stabilizing.
+const SOUP_MAX_GENERATIONS: u64 = 10_000;
+/// Population samples kept for the sparkline overlay.
+const SPARKLINE_HISTORY: usize = 256;
+/// Pixel height of the sparkline strip along the bottom edge.
+const SPARKLINE_HEIGHT: u32 = 24;
+/// Longest side of the minimap overlay, in pixels.
+const MINIMAP_SIZE: u32 = 96;
 
+/// Key bindings listed by the `H` help overlay, one line per entry.
+const HELP: &[&str] = &[
+    "space  pause    right/n  step",
+    "z  undo    shift+z  rewind",
+    "r  randomize    c  clear",
+    "i  invert    ctrl+scroll  fill rate",
+    "g  glider    o  glider gun",
+    "b  brians brain    d  rule preset",
+    "w  edge mode",
+    "t  palette    l  grid    f  stats",
+    "k  activity pulse    j  rainbow",
+    "x/y  mirror    e  rotate selection",
+    "[ ]  brush size    - =  speed",
+    "s  save    p  png    v  gif",
+    "u  single-buffer demo",
+    "m  graph    q  minimap",
+    "1-9  snapshot    shift+1-9  restore",
+    "home  recenter    f11  fullscreen",
+    "h  close this help",
+];
+
+/// Conway's Game of Life.
+#[derive(Parser)]
+struct Args {
+    /// Window width in pixels
+    #[arg(long, default_value_t = 640)]
+    width: u32,
+
+    /// Window height in pixels
+    #[arg(long, default_value_t = 480)]
+    height: u32,
+
+    /// Cell size in pixels
+    #[arg(long, default_value_t = 4)]
+    scale: u32,
+
+    /// Horizontal cell size in pixels, overriding --scale
+    #[arg(long, value_name = "PX")]
+    scale_x: Option<u32>,
+
+    /// Vertical cell size in pixels, overriding --scale
+    #[arg(long, value_name = "PX")]
+    scale_y: Option<u32>,
+
+    /// Fraction of cells initially alive, between 0 and 1
+    #[arg(long, default_value_t = 0.1)]
+    fill: f32,
+
+    /// Spatial shape of the random fill density
+    #[arg(long, value_name = "MODE", default_value = "uniform", value_parser = parse_fill_mode)]
+    fill_mode: FillMode,
+
+    /// Cellular automaton rule in B/S notation, e.g. B36/S23
+    #[arg(long, value_parser = parse_rule)]
+    rule: Option<Rule>,
+
+    /// Run a different rule inside a rectangle of the grid; repeatable,
+    /// with later regions winning where they overlap
+    #[arg(long, value_name = "X,Y,W,H,RULE", value_parser = parse_rule_region)]
+    rule_region: Vec<RuleRegion>,
+
+    /// Pattern file to start from instead of a random fill (.rle or
+    /// .cells); repeatable, with an optional `@X,Y` placement offset
+    #[arg(long, value_name = "FILE[@X,Y]", value_parser = parse_load_spec)]
+    load: Vec<LoadSpec>,
+
+    /// Start from these live cells, as space-separated `x,y` pairs, on
+    /// an otherwise empty board
+    #[arg(long, value_name = "\"X,Y X,Y ...\"", conflicts_with = "load", value_parser = parse_cell_list)]
+    cells: Option<CellList>,
+
+    /// Run N generations without a window and print throughput
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "1000")]
+    headless: Option<u64>,
+
+    /// Render to the terminal with block characters instead of a window
+    #[arg(long)]
+    terminal: bool,
+
+    /// Simulate N generations without a window, then exit
+    #[arg(long, value_name = "N")]
+    run: Option<u64>,
+
+    /// With --run, print the final board to stdout as `.`/`O` rows
+    #[arg(long, requires = "run")]
+    dump: bool,
+
+    /// With --run, render each generation as a PNG into this directory
+    #[arg(long, value_name = "DIR", requires = "run")]
+    frames: Option<std::path::PathBuf>,
+
+    /// With --headless, print per-phase update timings at the end
+    #[arg(long, requires = "headless")]
+    profile: bool,
+
+    /// With --run or --headless, write a generation,population CSV here
+    #[arg(long, value_name = "FILE")]
+    stats: Option<std::path::PathBuf>,
+
+    /// Record each generation's cells to this replay file
+    #[arg(long, value_name = "FILE")]
+    record: Option<std::path::PathBuf>,
+
+    /// Simulate on a background thread and render the latest generation,
+    /// so a slow frame never stalls the simulation; view-only controls
+    #[arg(long, conflicts_with_all = ["record", "replay"])]
+    threaded: bool,
+
+    /// Play back a recorded replay with seek controls instead of
+    /// simulating
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["load", "cells", "record"])]
+    replay: Option<std::path::PathBuf>,
+
+    /// Run N random soups to stabilization and log what each settles into
+    #[arg(long, value_name = "N", conflicts_with = "load")]
+    soup: Option<u64>,
+
+    /// Run until the board stabilizes, then report how long it took
+    #[arg(long)]
+    settle: bool,
+
+    /// Stop advancing after this many generations; also caps --settle
+    /// and --soup
+    #[arg(long, value_name = "N")]
+    max_gens: Option<u64>,
+
+    /// Step the simulation once every K rendered frames instead of on
+    /// a wall-clock timer, for machine-independent pacing
+    #[arg(long, value_name = "K", value_parser = clap::value_parser!(u32).range(1..))]
+    tick_frames: Option<u32>,
+
+    /// Grow the grid when cells reach an edge, up to MAX cells per side
+    #[arg(long, value_name = "MAX")]
+    grow: Option<u32>,
+
+    /// Probability that a cell flips at random each generation
+    #[arg(long, value_name = "P", default_value_t = 0.0)]
+    noise: f32,
+
+    /// Seed for the random fill, for reproducible runs
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Color for live cells as RRGGBB hex
+    #[arg(long, value_name = "RRGGBB", value_parser = parse_color)]
+    alive_color: Option<[u8; 4]>,
+
+    /// Color for dead cells as RRGGBB hex
+    #[arg(long, value_name = "RRGGBB", value_parser = parse_color)]
+    dead_color: Option<[u8; 4]>,
+}
+
+/// One `--load` occurrence: a pattern file and where to stamp it, or
+/// `None` to center it on the grid.
+#[derive(Clone)]
+struct LoadSpec {
+    path: std::path::PathBuf,
+    offset: Option<(i32, i32)>,
+}
+
+/// Parses a `--load` value like `gun.rle` or `gun.rle@10,10` for clap.
+fn parse_load_spec(s: &str) -> Result<LoadSpec, String> {
+    let Some((path, offset)) = s.rsplit_once('@') else {
+        return Ok(LoadSpec {
+            path: s.into(),
+            offset: None,
+        });
+    };
+    let Some((x, y)) = offset.split_once(',') else {
+        return Err(format!("expected X,Y after `@` in {s:?}"));
+    };
+    let x = x.parse::<i32>().map_err(|err| format!("bad X offset: {err}"))?;
+    let y = y.parse::<i32>().map_err(|err| format!("bad Y offset: {err}"))?;
+    Ok(LoadSpec {
+        path: path.into(),
+        offset: Some((x, y)),
+    })
+}
+
+/// The coordinate pairs given to `--cells`, as one clap value.
+#[derive(Clone)]
+struct CellList(Vec<(u32, u32)>);
+
+/// Parses a `--cells` list of space-separated `x,y` pairs for clap.
+fn parse_cell_list(s: &str) -> Result<CellList, String> {
+    s.split_whitespace()
+        .map(|pair| {
+            let Some((x, y)) = pair.split_once(',') else {
+                return Err(format!("expected `x,y`, got {pair:?}"));
+            };
+            let x = x.parse::<u32>().map_err(|err| format!("bad x in {pair:?}: {err}"))?;
+            let y = y.parse::<u32>().map_err(|err| format!("bad y in {pair:?}: {err}"))?;
+            Ok((x, y))
+        })
+        .collect::<Result<_, _>>()
+        .map(CellList)
+}
+
+/// Adapts [`Rule::parse`] errors for clap, which prints the message and
+/// exits non-zero.
+fn parse_rule(s: &str) -> Result<Rule, String> {
+    Rule::parse(s).map_err(|err| err.to_string())
+}
+
+/// Parses a `--rule-region` rectangle-and-rule value, e.g.
+/// `0,0,32,32,B36/S23`.
+fn parse_rule_region(s: &str) -> Result<RuleRegion, String> {
+    let parts: Vec<&str> = s.splitn(5, ',').collect();
+    let [x, y, width, height, rule] = parts[..] else {
+        return Err(format!("{s:?} is not in X,Y,W,H,RULE form"));
+    };
+    let number =
+        |value: &str| value.parse::<u32>().map_err(|err| format!("{value:?}: {err}"));
+    Ok(RuleRegion {
+        x: number(x)?,
+        y: number(y)?,
+        width: number(width)?,
+        height: number(height)?,
+        rule: parse_rule(rule)?,
+    })
+}
+
+/// Parses a `--fill-mode` name for clap.
+fn parse_fill_mode(s: &str) -> Result<FillMode, String> {
+    match s {
+        "uniform" => Ok(FillMode::Uniform),
+        "horizontal" => Ok(FillMode::Horizontal),
+        "radial" => Ok(FillMode::Radial),
+        _ => Err(format!(
+            "{s:?} is not a fill mode (expected uniform, horizontal, or radial)"
+        )),
+    }
+}
+
+/// Parses an `RRGGBB` hex triplet into an opaque RGBA color.
+fn parse_color(s: &str) -> Result<[u8; 4], String> {
+    let s = s.strip_prefix('#').unwrap_or(s);
+    if s.len() != 6 || !s.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("{s:?} is not an RRGGBB hex color"));
+    }
+    let channel = |i| u8::from_str_radix(&s[i..i + 2], 16).unwrap();
+    Ok([channel(0), channel(2), channel(4), 0xff])
+}
+
+impl Args {
+    /// Horizontal cell size: `--scale-x` when given, `--scale` otherwise.
+    fn scale_x(&self) -> u32 {
+        self.scale_x.unwrap_or(self.scale)
+    }
+
+    /// Vertical cell size: `--scale-y` when given, `--scale` otherwise.
+    fn scale_y(&self) -> u32 {
+        self.scale_y.unwrap_or(self.scale)
+    }
+
+    /// Grid dimensions mapping onto the window at the configured scales.
+    /// A window that is not a whole multiple of the cell size leaves the
+    /// remainder as a clipped border rather than misaligning cells.
+    fn grid_size(&self) -> (u32, u32) {
+        (self.width / self.scale_x(), self.height / self.scale_y())
+    }
+
+    /// Trims the requested window down to whole cells, so the logical
+    /// pixel buffer is exactly the grid times the cell scale. The buffer
+    /// then upscales to any window size with `pixels`' nearest-neighbour
+    /// filter without a fractional cell at the edge going blurry.
+    fn snap_to_whole_cells(&mut self) {
+        let (grid_width, grid_height) = self.grid_size();
+        let snapped = (grid_width * self.scale_x(), grid_height * self.scale_y());
+        if snapped != (self.width, self.height) {
+            log::warn!(
+                "{}x{} window trimmed to {}x{} to fit whole cells",
+                self.width,
+                self.height,
+                snapped.0,
+                snapped.1
+            );
+            (self.width, self.height) = snapped;
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err("--width and --height must be non-zero".to_string());
+        }
+        if self.scale == 0 || self.scale_x == Some(0) || self.scale_y == Some(0) {
+            return Err("cell scales must be non-zero".to_string());
+        }
+        if self.scale_x() > self.width || self.scale_y() > self.height {
+            return Err(format!(
+                "cell scale {}x{} does not fit the {}x{} window",
+                self.scale_x(),
+                self.scale_y(),
+                self.width,
+                self.height
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.fill) {
+            return Err(format!("--fill {} must be between 0 and 1", self.fill));
+        }
+        if !(0.0..=1.0).contains(&self.noise) {
+            return Err(format!("--noise {} must be between 0 and 1", self.noise));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 fn main() -> Result<(), Error> {
     env_logger::init();
+    let mut args = Args::parse();
+    if let Err(err) = args.validate() {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+    args.snap_to_whole_cells();
+
+    let mut rng = match args.seed {
+        Some(seed) => fastrand::Rng::with_seed(seed),
+        None => fastrand::Rng::new(),
+    };
+
+    if let Some(generations) = args.headless {
+        run_headless(&args, generations, &mut rng);
+        return Ok(());
+    }
+
+    if let Some(soups) = args.soup {
+        run_soup(&args, soups);
+        return Ok(());
+    }
+
+    if args.settle {
+        run_settle(&args, &mut rng);
+        return Ok(());
+    }
+
+    if let Some(generations) = args.run {
+        run_generations(&args, generations, &mut rng);
+        return Ok(());
+    }
+
+    if args.terminal {
+        run_terminal(&args, &mut rng);
+        return Ok(());
+    }
+
     let event_loop = EventLoop::new();
-    let mut input = WinitInputHelper::new();
-    let window = {
-        let size = LogicalSize::new(WIDTH as f64, HEIGHT as f64);
-        WindowBuilder::new()
-            .with_title("Game of Life")
-            .with_inner_size(size)
-            .with_min_inner_size(size)
-            .build(&event_loop)
-            .unwrap()
+    let window = build_window(&event_loop, &args);
+    // The buffer stays at the snapped logical size whatever the window
+    // becomes; `pixels` upscales it with nearest-neighbour sampling, so
+    // cells remain crisp squares at any window size.
+    let pixels = {
+        let window_size = window.inner_size();
+        let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
+        Pixels::new(args.width, args.height, surface_texture)?
     };
 
-    let mut pixels = {
+    if let Some(path) = &args.replay {
+        let result = File::open(path).and_then(Replay::load);
+        match result {
+            Ok(replay) => run_replay(event_loop, window, pixels, args, replay),
+            Err(err) => {
+                eprintln!("error: {}: {err}", path.display());
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if args.threaded {
+        run_threaded(event_loop, window, pixels, args, rng);
+    }
+
+    run(event_loop, window, pixels, args, rng)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+    console_log::init_with_level(log::Level::Info).expect("failed to initialize logger");
+    wasm_bindgen_futures::spawn_local(run_web());
+}
+
+/// Browser entry point: mounts the canvas into the page and creates the
+/// pixel buffer asynchronously, as required on the web target.
+#[cfg(target_arch = "wasm32")]
+async fn run_web() {
+    use winit::platform::web::WindowExtWebSys;
+
+    // There is no command line in the browser; run with the defaults.
+    let args = Args::parse_from(["game-of-life-rs"]);
+    let event_loop = EventLoop::new();
+    let window = build_window(&event_loop, &args);
+
+    web_sys::window()
+        .and_then(|win| win.document())
+        .and_then(|doc| doc.body())
+        .and_then(|body| body.append_child(&web_sys::Element::from(window.canvas())).ok())
+        .expect("couldn't append canvas to document body");
+
+    let pixels = {
         let window_size = window.inner_size();
         let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
-        Pixels::new(WIDTH, HEIGHT, surface_texture)?
+        Pixels::new_async(args.width, args.height, surface_texture)
+            .await
+            .expect("failed to create the pixel buffer")
+    };
+    run(event_loop, window, pixels, args, fastrand::Rng::new());
+}
+
+fn build_window(event_loop: &EventLoop<()>, args: &Args) -> winit::window::Window {
+    let size = LogicalSize::new(args.width as f64, args.height as f64);
+    WindowBuilder::new()
+        .with_title("Game of Life")
+        .with_inner_size(size)
+        .with_min_inner_size(size)
+        .build(event_loop)
+        .unwrap()
+}
+
+/// Turns a world into output on one backend. For the file and terminal
+/// backends a `render` call emits a complete frame; the window backend
+/// only rasterizes into its pixel buffer, since the event loop draws
+/// overlays onto the frame before presenting it.
+trait Renderer {
+    fn render(&mut self, world: &World);
+}
+
+/// The interactive window's backend, wrapping the `pixels` buffer the
+/// event loop also uses for input mapping, overlays, and presentation.
+struct PixelsRenderer {
+    pixels: Pixels,
+    frame_width: u32,
+}
+
+impl Renderer for PixelsRenderer {
+    fn render(&mut self, world: &World) {
+        world.draw(self.pixels.frame_mut(), self.frame_width);
+    }
+}
+
+/// Half-block terminal output; each `render` repaints from the home
+/// position. See `run_terminal` for the screen setup around it.
+#[cfg(not(target_arch = "wasm32"))]
+struct TerminalRenderer;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Renderer for TerminalRenderer {
+    /// Prints the grid with half-block characters, packing two cell rows
+    /// into each text row. The output is clipped to the terminal size.
+    fn render(&mut self, world: &World) {
+        let (cols, rows) = terminal_size::terminal_size()
+            .map_or((80, 24), |(width, height)| (width.0 as u32, height.0 as u32));
+        // Keep a line free for the status row below the grid.
+        let max_rows = rows.saturating_sub(2).max(1);
+
+        let mut out = String::from("\x1b[H");
+        for row in 0..world.height().div_ceil(2).min(max_rows) {
+            for x in 0..world.width().min(cols) {
+                let top = world.get(x, row * 2);
+                let bottom = row * 2 + 1 < world.height() && world.get(x, row * 2 + 1);
+                out.push(match (top, bottom) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                });
+            }
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "gen {} — pop {} ({:+})",
+            world.generation(), world.population(), world.population_delta()
+        ));
+        println!("{out}");
+    }
+}
+
+/// Writes each rendered generation as `frame-NNNNNN.png` in a directory,
+/// drawn through the world's own viewport — one cell per pixel for the
+/// headless worlds that keep the 1:1 default.
+#[cfg(not(target_arch = "wasm32"))]
+struct PngRenderer {
+    dir: std::path::PathBuf,
+    frame: Vec<u8>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PngRenderer {
+    fn new(dir: std::path::PathBuf) -> Self {
+        Self {
+            dir,
+            frame: Vec::new(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Renderer for PngRenderer {
+    fn render(&mut self, world: &World) {
+        self.frame
+            .resize(world.width() as usize * world.height() as usize * 4, 0);
+        world.draw(&mut self.frame, world.width());
+        let path = self.dir.join(format!("frame-{:06}.png", world.generation()));
+        if let Err(err) = image::save_buffer(
+            &path,
+            &self.frame,
+            world.width(),
+            world.height(),
+            image::ColorType::Rgba8,
+        ) {
+            log_error("image::save_buffer", err);
+        }
+    }
+}
+
+fn run(
+    event_loop: EventLoop<()>,
+    window: winit::window::Window,
+    pixels: Pixels,
+    args: Args,
+    mut rng: fastrand::Rng,
+) -> ! {
+    let mut input = WinitInputHelper::new();
+    let mut renderer = PixelsRenderer {
+        pixels,
+        frame_width: args.width,
+    };
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut world = initial_world(&args, &mut rng);
+    #[cfg(target_arch = "wasm32")]
+    let mut world = {
+        let (grid_width, grid_height) = args.grid_size();
+        World::new(grid_width, grid_height, args.fill, EdgeMode::Dead, &mut rng)
     };
-    let mut world = World::new(WIDTH / SCALE_FACTOR, HEIGHT / SCALE_FACTOR, FILL_RATE);
-    let mut last_update = now();
+    world.viewport.scale_x = args.scale_x();
+    world.viewport.scale_y = args.scale_y();
+    let mut last_update = Instant::now();
+    // Simulation time owed but not yet stepped, for the fixed-timestep loop.
+    let mut accumulator: f64 = 0.0;
+    let mut update_interval: f64 = 0.5;
+    let mut paused = false;
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut recorder: Option<gif::Encoder<BufWriter<File>>> = None;
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut recorded_frames = 0;
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut replay_recorder = open_recorder(&args, &world);
+    // Sub-cell panning remainder carried between middle-mouse drag events.
+    let mut pan_x = 0.0f32;
+    let mut pan_y = 0.0f32;
+    let mut brush_radius: u32 = 0;
+    // Fill rate the next `R` reseed will use, adjustable at runtime.
+    let mut fill_rate = args.fill;
+    // Position in Rule::PRESETS the next `D` press advances from.
+    let mut preset_index = 0;
+    // Performance overlay state: counters reset once per second.
+    let mut show_stats = false;
+    let mut show_help = false;
+    // Recent population counts for the sparkline, newest at the back.
+    let mut show_sparkline = false;
+    let mut show_minimap = false;
+    let mut population_history: VecDeque<usize> = VecDeque::new();
+    let mut stats_window = Instant::now();
+    let mut frame_count: u32 = 0;
+    let mut update_count: u32 = 0;
+    // Redraws since the last simulation step, for --tick-frames pacing.
+    let mut frames_since_update: u32 = 0;
+    let mut fps: u32 = 0;
+    let mut ups: u32 = 0;
+    // Rectangular selection for mirror/rotate: drag with Shift held.
+    let mut selection_start: Option<(i64, i64)> = None;
+    let mut selection: Option<(u32, u32, u32, u32)> = None;
+    // Named checkpoints on the number keys, distinct from the undo
+    // history: `1`-`9` saves a slot, `Shift` plus the key restores it.
+    let mut slots: [Option<World>; 9] = Default::default();
 
     event_loop.run(move |event, _, control_flow| {
         // Draw the current frame
         if let Event::RedrawRequested(_) = event {
-            world.draw(pixels.frame_mut());
-            if let Err(err) = pixels.render() {
+            renderer.render(&world);
+            frames_since_update += 1;
+
+            if show_stats {
+                frame_count += 1;
+                let elapsed = stats_window.elapsed().as_secs_f64();
+                if elapsed >= 1.0 {
+                    fps = (frame_count as f64 / elapsed).round() as u32;
+                    ups = (update_count as f64 / elapsed).round() as u32;
+                    frame_count = 0;
+                    update_count = 0;
+                    stats_window = Instant::now();
+                }
+                let stats = format!("{fps} fps {ups} ups");
+                draw_text(renderer.pixels.frame_mut(), args.width, 2, 2, &stats);
+            }
+
+            // The keymap draws over the running board, like the stats
+            // overlay, leaving room for the stats line above it.
+            if show_help {
+                let line_height = 6 * OVERLAY_SCALE + 2;
+                for (line, text) in HELP.iter().enumerate() {
+                    let y = 2 + line_height * (line as u32 + 1);
+                    draw_text(renderer.pixels.frame_mut(), args.width, 2, y, text);
+                }
+            }
+
+            if show_sparkline {
+                draw_sparkline(renderer.pixels.frame_mut(), args.width, args.height, &population_history);
+            }
+
+            if show_minimap {
+                draw_minimap(renderer.pixels.frame_mut(), args.width, args.height, &world);
+            }
+
+            // Append the frame to an in-progress GIF recording
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(encoder) = recorder.as_mut() {
+                let mut data = renderer.pixels.frame().to_vec();
+                let mut frame =
+                    gif::Frame::from_rgba_speed(args.width as u16, args.height as u16, &mut data, 10);
+                frame.delay = (update_interval * 100.0) as u16;
+                if let Err(err) = encoder.write_frame(&frame) {
+                    log_error("gif::Encoder::write_frame", err);
+                    recorder = None;
+                }
+                recorded_frames += 1;
+                if recorded_frames >= MAX_GIF_FRAMES {
+                    recorder = None;
+                    log::info!("recording stopped after {MAX_GIF_FRAMES} frames");
+                }
+            }
+
+            if let Err(err) = renderer.pixels.render() {
                 log_error("pixels.render", err);
                 *control_flow = ControlFlow::Exit;
                 return;
@@ -58,131 +693,1294 @@ fn main() -> Result<(), Error> {
                 return;
             }
 
+            // Pause and resume the simulation
+            if input.key_pressed(VirtualKeyCode::Space) {
+                paused = !paused;
+            }
+
+            // Advance exactly one generation
+            if input.key_pressed(VirtualKeyCode::Right) || input.key_pressed(VirtualKeyCode::N) {
+                world.update();
+                world.apply_noise(args.noise, &mut rng);
+                push_population(&mut population_history, world.population());
+                #[cfg(not(target_arch = "wasm32"))]
+                record_replay(&mut replay_recorder, &world);
+                update_title(&window, &world, brush_radius);
+                window.request_redraw();
+                last_update = Instant::now();
+                accumulator = 0.0;
+            }
+
+            // Toggle frozen wall cells with Ctrl+click
+            if input.held_control() && input.mouse_pressed(0) {
+                if let Some(pos) = input.mouse() {
+                    if let Ok((px, py)) = renderer.pixels.window_pos_to_pixel(pos) {
+                        let (x, y) = cursor_cell(&world, px, py);
+                        if (0..world.width() as i64).contains(&x)
+                            && (0..world.height() as i64).contains(&y)
+                        {
+                            let frozen = !world.is_frozen(x as u32, y as u32);
+                            world.set_frozen(x as u32, y as u32, frozen);
+                            window.request_redraw();
+                        }
+                    }
+                }
+            }
+
+            // Select a rectangular region by dragging with Shift held
+            if input.held_shift() && input.mouse_pressed(0) {
+                if let Some(pos) = input.mouse() {
+                    if let Ok((px, py)) = renderer.pixels.window_pos_to_pixel(pos) {
+                        selection_start = Some(cursor_cell(&world, px, py));
+                    }
+                }
+            }
+            if input.mouse_released(0) {
+                if let Some((sx, sy)) = selection_start.take() {
+                    if let Some(pos) = input.mouse() {
+                        if let Ok((px, py)) = renderer.pixels.window_pos_to_pixel(pos) {
+                            let (ex, ey) = cursor_cell(&world, px, py);
+                            let clamp_x = |v: i64| v.clamp(0, world.width() as i64 - 1) as u32;
+                            let clamp_y = |v: i64| v.clamp(0, world.height() as i64 - 1) as u32;
+                            let (x0, x1) = (clamp_x(sx.min(ex)), clamp_x(sx.max(ex)));
+                            let (y0, y1) = (clamp_y(sy.min(ey)), clamp_y(sy.max(ey)));
+                            selection = Some((x0, y0, x1 - x0 + 1, y1 - y0 + 1));
+                            log::info!(
+                                "selected {}x{} at ({x0}, {y0})",
+                                x1 - x0 + 1,
+                                y1 - y0 + 1
+                            );
+                        }
+                    }
+                }
+            }
+
+            // Mirror or rotate the selected region. The grid can shrink
+            // after the selection was made (restoring a smaller snapshot,
+            // say), so re-clip it first instead of letting the region
+            // methods panic on stale coordinates.
+            if let Some((x, y, width, height)) = selection {
+                let (grid_width, grid_height) = world.dimensions();
+                if x >= grid_width || y >= grid_height {
+                    selection = None;
+                } else {
+                    let width = width.min(grid_width - x);
+                    let height = height.min(grid_height - y);
+                    selection = Some((x, y, width, height));
+                    if input.key_pressed(VirtualKeyCode::X) {
+                        world.mirror_region_horizontal(x, y, width, height);
+                        window.request_redraw();
+                    }
+                    if input.key_pressed(VirtualKeyCode::Y) {
+                        world.mirror_region_vertical(x, y, width, height);
+                        window.request_redraw();
+                    }
+                    if input.key_pressed(VirtualKeyCode::E) {
+                        world.rotate_region_clockwise(x, y, width, height);
+                        // The rotated region has swapped dimensions, clipped
+                        // against the grid like the rotation itself.
+                        selection = Some((
+                            x,
+                            y,
+                            height.min(grid_width - x),
+                            width.min(grid_height - y),
+                        ));
+                        window.request_redraw();
+                    }
+                }
+            }
+
+            // Paint cells with the mouse: left button draws, right button
+            // erases, covering the square brush around the cursor
+            if !input.held_shift()
+                && !input.held_control()
+                && (input.mouse_held(0) || input.mouse_held(1))
+            {
+                if let Some(pos) = input.mouse() {
+                    if let Ok((px, py)) = renderer.pixels.window_pos_to_pixel(pos) {
+                        let (x, y) = cursor_cell(&world, px, py);
+                        let radius = brush_radius as i64;
+                        for cy in y - radius..=y + radius {
+                            for cx in x - radius..=x + radius {
+                                if (0..world.width() as i64).contains(&cx)
+                                    && (0..world.height() as i64).contains(&cy)
+                                {
+                                    world.set_cell(cx as u32, cy as u32, input.mouse_held(0));
+                                }
+                            }
+                        }
+                        window.request_redraw();
+                    }
+                }
+            }
+
+            // Shrink and grow the paint brush
+            if input.key_pressed(VirtualKeyCode::LBracket) && brush_radius > 0 {
+                brush_radius -= 1;
+                update_title(&window, &world, brush_radius);
+            }
+            if input.key_pressed(VirtualKeyCode::RBracket) && brush_radius < MAX_BRUSH_RADIUS {
+                brush_radius += 1;
+                update_title(&window, &world, brush_radius);
+            }
+
+            // Stamp a glider at the cursor
+            if input.key_pressed(VirtualKeyCode::G) {
+                if let Some(pos) = input.mouse() {
+                    if let Ok((px, py)) = renderer.pixels.window_pos_to_pixel(pos) {
+                        let (x, y) = cursor_cell(&world, px, py);
+                        world.stamp(patterns::GLIDER, x as i32, y as i32);
+                        window.request_redraw();
+                    }
+                }
+            }
+
+            // Pan the viewport by dragging with the middle mouse button
+            if input.mouse_held(2) {
+                let (dx, dy) = input.mouse_diff();
+                pan_x -= dx / world.viewport.scale_x.max(1) as f32;
+                pan_y -= dy / world.viewport.scale_y.max(1) as f32;
+                if pan_x.trunc() != 0.0 || pan_y.trunc() != 0.0 {
+                    world.viewport.x += pan_x.trunc() as i64;
+                    world.viewport.y += pan_y.trunc() as i64;
+                    pan_x = pan_x.fract();
+                    pan_y = pan_y.fract();
+                    window.request_redraw();
+                }
+            }
+
+            // Zoom with the scroll wheel, keeping the window center
+            // fixed; with Ctrl held, dial the fill rate the next reseed
+            // will use instead.
+            let scroll = input.scroll_diff();
+            if scroll != 0.0 && input.held_control() {
+                fill_rate = (fill_rate + scroll.signum() * 0.05).clamp(0.0, 1.0);
+                window.set_title(&format!(
+                    "Game of Life — fill {:.0}% (press r to reseed)",
+                    fill_rate * 100.0
+                ));
+            } else if scroll != 0.0 {
+                // Zooming doubles or halves both axes so the configured
+                // cell aspect ratio is preserved.
+                let zoom = |scale: u32| {
+                    if scroll > 0.0 {
+                        (scale * 2).min(64)
+                    } else {
+                        (scale / 2).max(1)
+                    }
+                };
+                let old_x = world.viewport.scale_x.max(1);
+                let old_y = world.viewport.scale_y.max(1);
+                let (new_x, new_y) = (zoom(old_x), zoom(old_y));
+                if (new_x, new_y) != (old_x, old_y) {
+                    world.viewport.x +=
+                        (args.width / 2 / old_x) as i64 - (args.width / 2 / new_x) as i64;
+                    world.viewport.y +=
+                        (args.height / 2 / old_y) as i64 - (args.height / 2 / new_y) as i64;
+                    world.viewport.scale_x = new_x;
+                    world.viewport.scale_y = new_y;
+                    window.request_redraw();
+                }
+            }
+
+            // Recenter the viewport on the live population
+            if input.key_pressed(VirtualKeyCode::Home) {
+                if let Some((min_x, min_y, max_x, max_y)) = world.live_bounds() {
+                    let scale_x = world.viewport.scale_x.max(1);
+                    let scale_y = world.viewport.scale_y.max(1);
+                    world.viewport.x =
+                        (min_x + max_x) as i64 / 2 - (args.width / scale_x) as i64 / 2;
+                    world.viewport.y =
+                        (min_y + max_y) as i64 / 2 - (args.height / scale_y) as i64 / 2;
+                    window.request_redraw();
+                }
+            }
+
+            // Step backwards through the bounded undo history; Shift
+            // rewinds several generations at once
+            if input.key_pressed(VirtualKeyCode::Z) {
+                let steps = if input.held_shift() { REWIND_STEPS } else { 1 };
+                if world.rewind(steps) > 0 {
+                    update_title(&window, &world, brush_radius);
+                    window.request_redraw();
+                }
+            }
+
+            // Clear the board and place a Gosper glider gun in the top-left
+            if input.key_pressed(VirtualKeyCode::O) {
+                world.clear();
+                world.stamp(patterns::GOSPER_GLIDER_GUN, 1, 1);
+                update_title(&window, &world, brush_radius);
+                window.request_redraw();
+            }
+
+            // Save the board to a timestamped .cells file
+            #[cfg(not(target_arch = "wasm32"))]
+            if input.key_pressed(VirtualKeyCode::S) {
+                let path = format!("life-{}.cells", epoch_secs());
+                match File::create(&path).and_then(|file| world.save_cells(BufWriter::new(file))) {
+                    Ok(()) => log::info!("saved board to {path}"),
+                    Err(err) => log_error("save_cells", err),
+                }
+            }
+
+            // Start or stop recording the simulation to recording.gif
+            #[cfg(not(target_arch = "wasm32"))]
+            if input.key_pressed(VirtualKeyCode::V) {
+                if recorder.is_some() {
+                    // Dropping the encoder flushes the file.
+                    recorder = None;
+                    log::info!("saved recording.gif ({recorded_frames} frames)");
+                } else {
+                    match File::create("recording.gif").map_err(gif::EncodingError::from).and_then(
+                        |file| {
+                            gif::Encoder::new(
+                                BufWriter::new(file),
+                                args.width as u16,
+                                args.height as u16,
+                                &[],
+                            )
+                        },
+                    ) {
+                        Ok(encoder) => {
+                            recorder = Some(encoder);
+                            recorded_frames = 0;
+                            log::info!("recording to recording.gif");
+                        }
+                        Err(err) => log_error("gif::Encoder::new", err),
+                    }
+                }
+            }
+
+            // Save the rendered frame as a PNG screenshot
+            #[cfg(not(target_arch = "wasm32"))]
+            if input.key_pressed(VirtualKeyCode::P) {
+                let path = format!("screenshot-{}.png", world.generation());
+                match image::save_buffer(
+                    &path,
+                    renderer.pixels.frame(),
+                    args.width,
+                    args.height,
+                    image::ColorType::Rgba8,
+                ) {
+                    Ok(()) => log::info!("saved screenshot to {path}"),
+                    Err(err) => log_error("image::save_buffer", err),
+                }
+            }
+
+            // Reseed the board at the current fill rate
+            if input.key_pressed(VirtualKeyCode::R) {
+                world.randomize_with(fill_rate, args.fill_mode, &mut rng);
+                update_title(&window, &world, brush_radius);
+                window.request_redraw();
+            }
+
+            // Clear the board
+            if input.key_pressed(VirtualKeyCode::C) {
+                world.clear();
+                update_title(&window, &world, brush_radius);
+                window.request_redraw();
+            }
+
+            // Invert the board
+            if input.key_pressed(VirtualKeyCode::I) {
+                world.invert();
+                update_title(&window, &world, brush_radius);
+                window.request_redraw();
+            }
+
+            // Toggle the FPS / update-rate overlay
+            if input.key_pressed(VirtualKeyCode::F) {
+                show_stats = !show_stats;
+                stats_window = Instant::now();
+                frame_count = 0;
+                update_count = 0;
+                window.request_redraw();
+            }
+
+            // Snapshot the board into a numbered slot, or restore one
+            const SLOT_KEYS: [VirtualKeyCode; 9] = [
+                VirtualKeyCode::Key1,
+                VirtualKeyCode::Key2,
+                VirtualKeyCode::Key3,
+                VirtualKeyCode::Key4,
+                VirtualKeyCode::Key5,
+                VirtualKeyCode::Key6,
+                VirtualKeyCode::Key7,
+                VirtualKeyCode::Key8,
+                VirtualKeyCode::Key9,
+            ];
+            for (slot, key) in SLOT_KEYS.iter().enumerate() {
+                if !input.key_pressed(*key) {
+                    continue;
+                }
+                if input.held_shift() {
+                    match &slots[slot] {
+                        Some(saved) => {
+                            world = saved.clone();
+                            log::info!("restored snapshot slot {}", slot + 1);
+                            update_title(&window, &world, brush_radius);
+                            window.request_redraw();
+                        }
+                        None => log::info!("snapshot slot {} is empty", slot + 1),
+                    }
+                } else {
+                    slots[slot] = Some(world.clone());
+                    log::info!("saved snapshot slot {}", slot + 1);
+                }
+            }
+
+            // Toggle the deliberately-wrong single-buffer update, a
+            // teaching demo of why double buffering matters
+            if input.key_pressed(VirtualKeyCode::U) {
+                world.single_buffer = !world.single_buffer;
+                log::info!(
+                    "single-buffer update {}",
+                    if world.single_buffer { "on" } else { "off" }
+                );
+            }
+
+            // Toggle the activity-pulsing background
+            if input.key_pressed(VirtualKeyCode::K) {
+                world.activity_pulse = !world.activity_pulse;
+                window.request_redraw();
+            }
+
+            // Toggle rainbow cell coloring
+            if input.key_pressed(VirtualKeyCode::J) {
+                world.rainbow = !world.rainbow;
+                window.request_redraw();
+            }
+
+            // Toggle the population history sparkline
+            if input.key_pressed(VirtualKeyCode::M) {
+                show_sparkline = !show_sparkline;
+                window.request_redraw();
+            }
+
+            // Cycle through the preset rules without touching the board
+            if input.key_pressed(VirtualKeyCode::D) {
+                preset_index = (preset_index + 1) % Rule::PRESETS.len();
+                let (name, rule) = Rule::PRESETS[preset_index];
+                world.set_rule(rule);
+                window.set_title(&format!("Game of Life — rule {name}"));
+                window.request_redraw();
+            }
+
+            // Toggle the whole-world minimap
+            if input.key_pressed(VirtualKeyCode::Q) {
+                show_minimap = !show_minimap;
+                window.request_redraw();
+            }
+
+            // Toggle the key-binding help overlay
+            if input.key_pressed(VirtualKeyCode::H) {
+                show_help = !show_help;
+                window.request_redraw();
+            }
+
+            // Toggle the grid overlay
+            if input.key_pressed(VirtualKeyCode::L) {
+                world.grid_overlay = !world.grid_overlay;
+                window.request_redraw();
+            }
+
+            // Cycle through the built-in palette presets
+            if input.key_pressed(VirtualKeyCode::T) {
+                let next = Palette::PRESETS
+                    .iter()
+                    .position(|preset| *preset == world.palette)
+                    .map_or(0, |i| (i + 1) % Palette::PRESETS.len());
+                world.palette = Palette::PRESETS[next];
+                window.request_redraw();
+            }
+
+            // Switch between Brian's Brain and Conway's Life
+            if input.key_pressed(VirtualKeyCode::B) {
+                world.set_rule(if world.rule() == Rule::BRIANS_BRAIN {
+                    Rule::CONWAY
+                } else {
+                    Rule::BRIANS_BRAIN
+                });
+                update_title(&window, &world, brush_radius);
+            }
+
+            // Toggle borderless fullscreen; the resize event that follows
+            // brings the pixels surface along
+            if input.key_pressed(VirtualKeyCode::F11) {
+                if window.fullscreen().is_some() {
+                    window.set_fullscreen(None);
+                    window.set_inner_size(LogicalSize::new(args.width as f64, args.height as f64));
+                } else {
+                    window.set_fullscreen(Some(Fullscreen::Borderless(None)));
+                }
+            }
+
+            // Cycle the edge behavior: dead border, torus wrap, mirror
+            if input.key_pressed(VirtualKeyCode::W) {
+                world.edge_mode = match world.edge_mode {
+                    EdgeMode::Dead => EdgeMode::Wrap,
+                    EdgeMode::Wrap => EdgeMode::Mirror,
+                    EdgeMode::Mirror => EdgeMode::Dead,
+                };
+            }
+
+            // Speed the simulation up and down
+            if input.key_pressed(VirtualKeyCode::Minus)
+                || input.key_pressed(VirtualKeyCode::NumpadSubtract)
+            {
+                update_interval =
+                    (update_interval * UPDATE_INTERVAL_FACTOR).min(MAX_UPDATE_INTERVAL);
+            }
+            if input.key_pressed(VirtualKeyCode::Equals)
+                || input.key_pressed(VirtualKeyCode::NumpadAdd)
+            {
+                update_interval =
+                    (update_interval / UPDATE_INTERVAL_FACTOR).max(MIN_UPDATE_INTERVAL);
+            }
+
             // Resize the window
             if let Some(size) = input.window_resized() {
-                if let Err(err) = pixels.resize_surface(size.width, size.height) {
+                if let Err(err) = renderer.pixels.resize_surface(size.width, size.height) {
                     log_error("pixels.resize_surface", err);
                     *control_flow = ControlFlow::Exit;
                     return;
                 }
+                window.request_redraw();
             }
 
-            // Update internal state and request a redraw
-            let now = now();
-            if (now - last_update) > 0.5 {
-                world.update();
+            // Run the simulation on a fixed timestep: bank the elapsed
+            // time and step as many whole intervals as it covers, so the
+            // effective rate stays steady through rendering hiccups.
+            // With --tick-frames, step by redraw count instead, so runs
+            // pace identically regardless of machine speed.
+            let now = Instant::now();
+            if !paused && args.tick_frames.is_some() {
+                // Keep redraws flowing so the frame counter advances.
                 window.request_redraw();
-                last_update = now;
+                if args.tick_frames.is_some_and(|k| frames_since_update >= k) {
+                    frames_since_update = 0;
+                    if args.max_gens.is_some_and(|limit| world.generation() >= limit) {
+                        paused = true;
+                    } else {
+                        world.update();
+                        world.apply_noise(args.noise, &mut rng);
+                        push_population(&mut population_history, world.population());
+                        #[cfg(not(target_arch = "wasm32"))]
+                        record_replay(&mut replay_recorder, &world);
+                        update_count += 1;
+                        update_title(&window, &world, brush_radius);
+                        if world.period().is_some() {
+                            paused = true;
+                        }
+                    }
+                }
+            } else if !paused {
+                accumulator += (now - last_update).as_secs_f64();
+                accumulator = accumulator.min(update_interval * MAX_UPDATE_BACKLOG);
+                let mut updated = false;
+                while accumulator >= update_interval {
+                    // Stop an unattended demo once the --max-gens budget
+                    // is spent, leaving the final state on screen.
+                    if args.max_gens.is_some_and(|limit| world.generation() >= limit) {
+                        paused = true;
+                        accumulator = 0.0;
+                        break;
+                    }
+                    world.update();
+                    world.apply_noise(args.noise, &mut rng);
+                    push_population(&mut population_history, world.population());
+                    #[cfg(not(target_arch = "wasm32"))]
+                    record_replay(&mut replay_recorder, &world);
+                    accumulator -= update_interval;
+                    updated = true;
+                    update_count += 1;
+                    // Auto-pause once the board settles into a still life
+                    // or a short cycle.
+                    if world.period().is_some() {
+                        paused = true;
+                        accumulator = 0.0;
+                        break;
+                    }
+                }
+                if updated {
+                    update_title(&window, &world, brush_radius);
+                    window.request_redraw();
+                }
             }
+            last_update = now;
         }
     });
 }
 
-fn log_error<E: std::error::Error + 'static>(method_name: &str, err: E) {
-    error!("{method_name}() failed: {err}");
-    for source in err.sources().skip(1) {
-        error!("  Caused by: {source}");
-    }
-}
+/// Runs the simulation on a dedicated thread, decoupled from rendering:
+/// the worker pushes each generation into a bounded channel and the
+/// event loop only draws the newest one it finds, so a slow frame never
+/// stalls the simulation and a fast simulation never blocks the UI.
+/// The board is owned by the worker, so this mode is view-only: `Space`
+/// pauses, the scroll wheel zooms, and `Escape` quits. The worker stops
+/// on its own when the channel closes or the board stabilizes.
+#[cfg(not(target_arch = "wasm32"))]
+fn run_threaded(
+    event_loop: EventLoop<()>,
+    window: winit::window::Window,
+    mut pixels: Pixels,
+    args: Args,
+    mut rng: fastrand::Rng,
+) -> ! {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc};
 
-fn now() -> f64 {
-    let now = SystemTime::now();
-    let duration = now
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .expect("Time went backwards!");
-    duration.as_secs_f64()
-}
+    let mut input = WinitInputHelper::new();
+    let mut world = initial_world(&args, &mut rng);
+    let mut viewport = game_of_life_rs::Viewport {
+        scale_x: args.scale_x(),
+        scale_y: args.scale_y(),
+        ..Default::default()
+    };
+    world.viewport = viewport;
 
-struct Cell {
-    alive: bool,
-}
+    let paused = Arc::new(AtomicBool::new(false));
+    // Capacity bounds the worker's lead: with the renderer stalled it
+    // banks at most two generations before blocking on `send`.
+    let (sender, receiver) = mpsc::sync_channel::<World>(2);
+    let worker_paused = Arc::clone(&paused);
+    let noise = args.noise;
+    let max_gens = args.max_gens;
+    let mut sim = world.clone();
+    std::thread::spawn(move || loop {
+        if worker_paused.load(Ordering::Relaxed)
+            || max_gens.is_some_and(|limit| sim.generation() >= limit)
+        {
+            std::thread::sleep(Duration::from_millis(10));
+            continue;
+        }
+        sim.update();
+        sim.apply_noise(noise, &mut rng);
+        if sim.period().is_some() {
+            worker_paused.store(true, Ordering::Relaxed);
+        }
+        // The send fails once the event loop is gone; stop simulating.
+        if sender.send(sim.clone()).is_err() {
+            return;
+        }
+    });
 
-impl Cell {
-    fn update(&mut self, num_neighbours: u8) {
-        self.alive = (num_neighbours == 3) || (self.alive && num_neighbours == 2)
-    }
-}
+    event_loop.run(move |event, _, control_flow| {
+        if let Event::RedrawRequested(_) = event {
+            world.draw(pixels.frame_mut(), args.width);
+            if let Err(err) = pixels.render() {
+                log_error("pixels.render", err);
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
+        }
 
-struct World {
-    width: u32,
-    height: u32,
-    cells: Vec<Cell>,
+        if input.update(&event) {
+            if input.key_pressed(VirtualKeyCode::Escape) || input.close_requested() {
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
+
+            if input.key_pressed(VirtualKeyCode::Space) {
+                paused.fetch_xor(true, Ordering::Relaxed);
+            }
+
+            // Zoom with the scroll wheel; the viewport lives on this
+            // thread's copy and is reapplied to every generation shown.
+            let scroll = input.scroll_diff();
+            if scroll != 0.0 {
+                let zoom = |scale: u32| {
+                    if scroll > 0.0 {
+                        (scale * 2).min(64)
+                    } else {
+                        (scale / 2).max(1)
+                    }
+                };
+                viewport.scale_x = zoom(viewport.scale_x.max(1));
+                viewport.scale_y = zoom(viewport.scale_y.max(1));
+                world.viewport = viewport;
+                window.request_redraw();
+            }
+
+            if let Some(size) = input.window_resized() {
+                if let Err(err) = pixels.resize_surface(size.width, size.height) {
+                    log_error("pixels.resize_surface", err);
+                    *control_flow = ControlFlow::Exit;
+                    return;
+                }
+            }
+
+            // Show the newest generation the worker has produced,
+            // skipping any it got ahead by.
+            let mut updated = false;
+            while let Ok(next) = receiver.try_recv() {
+                world = next;
+                updated = true;
+            }
+            if updated {
+                world.viewport = viewport;
+                update_title(&window, &world, 0);
+                window.request_redraw();
+            }
+        }
+    })
 }
 
-impl World {
-    fn new(width: u32, height: u32, fill_rate: f32) -> Self {
-        let num_cells = (width * height) as usize;
-        let mut cells: Vec<Cell> = Vec::with_capacity(num_cells);
-        cells.resize_with(num_cells, || Cell {
-            alive: fastrand::f32() < fill_rate,
-        });
+/// Plays back a recording instead of simulating: `Space` pauses,
+/// `Left`/`Right` step one generation, `Home`/`End` jump to the start
+/// and the final generation.
+#[cfg(not(target_arch = "wasm32"))]
+fn run_replay(
+    event_loop: EventLoop<()>,
+    window: winit::window::Window,
+    mut pixels: Pixels,
+    args: Args,
+    replay: Replay,
+) -> ! {
+    let mut input = WinitInputHelper::new();
+    let last_generation = replay.len() - 1;
+    let mut index = 0usize;
+    let mut playing = true;
+    let mut last_step = Instant::now();
+    let mut world = replay.world_at(0);
+    world.viewport.scale_x = args.scale_x();
+    world.viewport.scale_y = args.scale_y();
+    window.set_title(&format!("Game of Life — replay 0/{last_generation}"));
 
-        Self {
-            width,
-            height,
-            cells,
+    event_loop.run(move |event, _, control_flow| {
+        if let Event::RedrawRequested(_) = event {
+            world.draw(pixels.frame_mut(), args.width);
+            if let Err(err) = pixels.render() {
+                log_error("pixels.render", err);
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
         }
-    }
 
-    fn update(&mut self) {
-        let mut neighbours: Vec<u8> = Vec::with_capacity(self.cells.len());
-        for i in 0..self.cells.len() {
-            let w = self.width as usize;
-            let h = self.height as usize;
-            let x = i % w;
-            let y = i / w;
-            let mut neighbour_coords: Vec<usize> = Vec::new();
+        if input.update(&event) {
+            if input.key_pressed(VirtualKeyCode::Escape) || input.close_requested() {
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
 
-            if y > 0 {
-                if x > 0 {
-                    neighbour_coords.push(i - w - 1);
+            let mut seek = None;
+            if input.key_pressed(VirtualKeyCode::Space) {
+                playing = !playing;
+                last_step = Instant::now();
+            }
+            if input.key_pressed(VirtualKeyCode::Right) {
+                seek = Some(index.saturating_add(1));
+                playing = false;
+            }
+            if input.key_pressed(VirtualKeyCode::Left) {
+                seek = Some(index.saturating_sub(1));
+                playing = false;
+            }
+            if input.key_pressed(VirtualKeyCode::Home) {
+                seek = Some(0);
+            }
+            if input.key_pressed(VirtualKeyCode::End) {
+                seek = Some(last_generation);
+                playing = false;
+            }
+            if playing && last_step.elapsed().as_secs_f64() >= 0.1 {
+                if index < last_generation {
+                    seek = Some(index + 1);
+                    last_step = Instant::now();
+                } else {
+                    playing = false;
                 }
-                if x < (w - 1) {
-                    neighbour_coords.push(i - w + 1);
+            }
+
+            if let Some(target) = seek {
+                index = target.min(last_generation);
+                world = replay.world_at(index);
+                world.viewport.scale_x = args.scale_x();
+                world.viewport.scale_y = args.scale_y();
+                window.set_title(&format!("Game of Life — replay {index}/{last_generation}"));
+                window.request_redraw();
+            }
+
+            if let Some(size) = input.window_resized() {
+                if let Err(err) = pixels.resize_surface(size.width, size.height) {
+                    log_error("pixels.resize_surface", err);
+                    *control_flow = ControlFlow::Exit;
                 }
-                neighbour_coords.push(i - w)
             }
-            if y < (h - 1) {
-                if x > 0 {
-                    neighbour_coords.push(i + w - 1);
+        }
+    })
+}
+
+/// Builds the starting world: the board seeded from the arguments, with
+/// the `--rule` override, any `--rule-region` rectangles, the `--grow`
+/// limit and the palette overrides applied on top, so every run mode
+/// honors them.
+#[cfg(not(target_arch = "wasm32"))]
+fn initial_world(args: &Args, rng: &mut fastrand::Rng) -> World {
+    let mut world = seed_board(args, rng);
+    if let Some(rule) = args.rule {
+        world.set_rule(rule);
+    }
+    for &region in &args.rule_region {
+        world.add_rule_region(region);
+    }
+    world.grow_limit = args.grow.map(|max| (max, max));
+    if let Some(alive) = args.alive_color {
+        world.palette.alive = alive;
+    }
+    if let Some(dead) = args.dead_color {
+        world.palette.dead = dead;
+    }
+    world
+}
+
+/// Seeds the board: the `--load` patterns stamped onto an empty grid
+/// when given, or a random fill otherwise. Load failures print an error
+/// and exit, matching how invalid arguments are handled.
+#[cfg(not(target_arch = "wasm32"))]
+fn seed_board(args: &Args, rng: &mut fastrand::Rng) -> World {
+    let (grid_width, grid_height) = args.grid_size();
+    if let Some(cells) = &args.cells {
+        let mut world = World::from_cells(
+            grid_width,
+            grid_height,
+            &vec![false; (grid_width * grid_height) as usize],
+        );
+        for &(x, y) in &cells.0 {
+            if x >= grid_width || y >= grid_height {
+                eprintln!("error: --cells {x},{y} is outside the {grid_width}x{grid_height} grid");
+                std::process::exit(1);
+            }
+        }
+        let pattern: Vec<(i32, i32)> = cells.0.iter().map(|&(x, y)| (x as i32, y as i32)).collect();
+        world.stamp(&pattern, 0, 0);
+        return world;
+    }
+    if args.load.is_empty() {
+        let mut world = World::new(grid_width, grid_height, args.fill, EdgeMode::Dead, rng);
+        if args.fill_mode != FillMode::Uniform {
+            world.randomize_with(args.fill, args.fill_mode, rng);
+        }
+        return world;
+    }
+
+    // A single file with no placement keeps the original behavior:
+    // centered on the grid, honoring an RLE header rule.
+    if let [spec] = args.load.as_slice() {
+        if spec.offset.is_none() {
+            return load_world(&spec.path, grid_width, grid_height);
+        }
+    }
+
+    // Compose several patterns onto an empty board; overlapping stamps
+    // OR together, so a later file never erases an earlier one. The
+    // first RLE header declaring a rule sets it for the whole board,
+    // and later headers that disagree are warned about and ignored.
+    let mut world = World::from_cells(
+        grid_width,
+        grid_height,
+        &vec![false; (grid_width * grid_height) as usize],
+    );
+    let mut header_rule: Option<Rule> = None;
+    for spec in &args.load {
+        let (pattern, rule) = load_pattern(&spec.path);
+        match (header_rule, rule) {
+            (None, Some(rule)) => header_rule = Some(rule),
+            (Some(kept), Some(rule)) if rule != kept => log::warn!(
+                "{}: header rule {rule:?} conflicts with {kept:?}; keeping {kept:?}",
+                spec.path.display()
+            ),
+            _ => {}
+        }
+        let (x, y) = spec.offset.unwrap_or((0, 0));
+        world.stamp(&pattern, x, y);
+    }
+    if let Some(rule) = header_rule {
+        world.set_rule(rule);
+    }
+    world
+}
+
+/// Loads a single pattern file as a whole world sized to the grid.
+#[cfg(not(target_arch = "wasm32"))]
+fn load_world(path: &std::path::Path, width: u32, height: u32) -> World {
+    let result = File::open(path)
+        .map_err(|err| err.to_string())
+        .and_then(|file| {
+            let reader = std::io::BufReader::new(file);
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("rle") => {
+                    World::load_rle_sized(reader, width, height).map_err(|err| err.to_string())
                 }
-                if x < (w - 1) {
-                    neighbour_coords.push(i + w + 1);
+                Some("cells") => {
+                    World::load_cells_sized(reader, width, height).map_err(|err| err.to_string())
                 }
-                neighbour_coords.push(i + w)
-            }
-            if x > 0 {
-                neighbour_coords.push(i - 1);
+                _ => Err("unknown pattern format (expected .rle or .cells)".to_string()),
             }
-            if x < (w - 1) {
-                neighbour_coords.push(i + 1);
+        });
+    match result {
+        Ok(world) => world,
+        Err(err) => {
+            eprintln!("error: {}: {err}", path.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Loads a pattern file as live-cell coordinates relative to its
+/// top-left corner, ready for [`World::stamp`], along with the rule its
+/// header declares for formats that carry one.
+#[cfg(not(target_arch = "wasm32"))]
+fn load_pattern(path: &std::path::Path) -> (Vec<(i32, i32)>, Option<Rule>) {
+    let result = File::open(path)
+        .map_err(|err| err.to_string())
+        .and_then(|file| {
+            let reader = std::io::BufReader::new(file);
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("rle") => load_rle_pattern_with_rule(reader).map_err(|err| err.to_string()),
+                Some("cells") => load_cells_pattern(reader)
+                    .map(|pattern| (pattern, None))
+                    .map_err(|err| err.to_string()),
+                _ => Err("unknown pattern format (expected .rle or .cells)".to_string()),
             }
+        });
+    match result {
+        Ok(pattern) => pattern,
+        Err(err) => {
+            eprintln!("error: {}: {err}", path.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Opens the `--record` replay file and writes the starting state.
+/// Failures to create it exit like any other bad argument.
+#[cfg(not(target_arch = "wasm32"))]
+fn open_recorder(args: &Args, world: &World) -> Option<ReplayRecorder<BufWriter<File>>> {
+    let path = args.record.as_ref()?;
+    let result = File::create(path)
+        .and_then(|file| ReplayRecorder::new(BufWriter::new(file), world));
+    match result {
+        Ok(recorder) => Some(recorder),
+        Err(err) => {
+            eprintln!("error: {}: {err}", path.display());
+            std::process::exit(1);
+        }
+    }
+}
 
-            let num_neighbours = neighbour_coords
-                .into_iter()
-                .filter(|j| self.cells[*j].alive)
-                .count();
+/// Appends the current state to the active recording, dropping the
+/// recorder after a write failure rather than logging every frame.
+#[cfg(not(target_arch = "wasm32"))]
+fn record_replay(recorder: &mut Option<ReplayRecorder<BufWriter<File>>>, world: &World) {
+    if let Some(active) = recorder.as_mut() {
+        if let Err(err) = active.record(world) {
+            log_error("ReplayRecorder::record", err);
+            *recorder = None;
+        }
+    }
+}
 
-            neighbours.push(num_neighbours as u8);
+/// Creates the `--frames` output directory and the PNG renderer that
+/// writes into it. Failures exit like any other bad argument.
+#[cfg(not(target_arch = "wasm32"))]
+fn open_frames(args: &Args) -> Option<Box<dyn Renderer>> {
+    let dir = args.frames.as_ref()?;
+    if let Err(err) = std::fs::create_dir_all(dir) {
+        eprintln!("error: {}: {err}", dir.display());
+        std::process::exit(1);
+    }
+    Some(Box::new(PngRenderer::new(dir.clone())))
+}
+
+/// Opens the `--stats` CSV and writes its header row. Failures to create
+/// or write the file exit like any other bad argument.
+#[cfg(not(target_arch = "wasm32"))]
+fn open_stats(args: &Args) -> Option<BufWriter<File>> {
+    let path = args.stats.as_ref()?;
+    let result = File::create(path).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "generation,population")?;
+        Ok(writer)
+    });
+    match result {
+        Ok(writer) => Some(writer),
+        Err(err) => {
+            eprintln!("error: {}: {err}", path.display());
+            std::process::exit(1);
         }
+    }
+}
 
-        for i in 0..self.cells.len() {
-            self.cells[i].update(neighbours[i]);
+/// Appends one `generation,population` row, or flushes and closes the
+/// writer when the run is over.
+#[cfg(not(target_arch = "wasm32"))]
+fn record_stats(stats: &mut Option<BufWriter<File>>, world: &World, finish: bool) {
+    let Some(writer) = stats.as_mut() else {
+        return;
+    };
+    let result = if finish {
+        writer.flush()
+    } else {
+        writeln!(writer, "{},{}", world.generation(), world.population())
+    };
+    if let Err(err) = result {
+        eprintln!("error: writing stats: {err}");
+        std::process::exit(1);
+    }
+    if finish {
+        *stats = None;
+    }
+}
+
+/// Surveys the rule's behavior by running successive random soups: each
+/// seed fills the grid, runs to stabilization, and the outcome is logged
+/// so interesting seeds can be revisited. Soups that are still changing
+/// after [`SOUP_MAX_GENERATIONS`] are reported as such.
+#[cfg(not(target_arch = "wasm32"))]
+fn run_soup(args: &Args, soups: u64) {
+    let base_seed = args.seed.unwrap_or(0);
+    let cap = args.max_gens.unwrap_or(SOUP_MAX_GENERATIONS);
+    for seed in base_seed..base_seed + soups {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        let mut world = initial_world(args, &mut rng);
+        while world.period().is_none() && world.generation() < cap {
+            world.update();
+        }
+        match world.period() {
+            Some(period) => println!(
+                "seed {seed}: period {period} at generation {} with population {}",
+                world.generation(), world.population()
+            ),
+            None => println!("seed {seed}: still active after {cap} generations"),
+        }
+    }
+}
+
+/// Answers "how long until this board settles?": runs the seed or loaded
+/// pattern until the still-life/oscillator detector fires, bounded by
+/// `--max-gens` so non-converging inputs still terminate.
+#[cfg(not(target_arch = "wasm32"))]
+fn run_settle(args: &Args, rng: &mut fastrand::Rng) {
+    let cap = args.max_gens.unwrap_or(SOUP_MAX_GENERATIONS);
+    let mut world = initial_world(args, rng);
+    while world.period().is_none() && world.generation() < cap {
+        world.update();
+    }
+    match world.period() {
+        Some(period) => println!(
+            "settled after {} generations with population {} (period {period})",
+            world.generation(), world.population()
+        ),
+        None => println!(
+            "still active after {cap} generations with population {}",
+            world.population()
+        ),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_headless(args: &Args, generations: u64, rng: &mut fastrand::Rng) {
+    let mut world = initial_world(args, rng);
+    world.profile = args.profile;
+    let mut stats = open_stats(args);
+    record_stats(&mut stats, &world, false);
+    let start = std::time::Instant::now();
+    for _ in 0..generations {
+        world.update();
+        world.apply_noise(args.noise, rng);
+        record_stats(&mut stats, &world, false);
+    }
+    record_stats(&mut stats, &world, true);
+    let elapsed = start.elapsed().as_secs_f64();
+    println!(
+        "{generations} generations in {elapsed:.3}s ({:.0} generations/sec)",
+        generations as f64 / elapsed
+    );
+    if args.profile {
+        let timings = world.profile_timings();
+        let updates = timings.updates.max(1) as f64;
+        println!("phase       total        average");
+        for (phase, total) in [("neighbours", timings.neighbours), ("rule", timings.rule)] {
+            println!(
+                "{phase:<11} {:>9.3}s {:>11.1}us",
+                total.as_secs_f64(),
+                total.as_secs_f64() * 1e6 / updates
+            );
         }
     }
+}
 
-    fn draw(&self, frame: &mut [u8]) {
-        for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
-            let x = (i % WIDTH as usize) as u32;
-            let y = (i / WIDTH as usize) as u32;
-            let j = ((y / SCALE_FACTOR) * self.width + (x / SCALE_FACTOR)) as usize;
-            let rgba = if self.cells[j].alive {
-                [0x5e, 0x48, 0xe8, 0xff]
+/// Returns the 3x5 bitmap glyph for an overlay character, one row per
+/// element with the leftmost pixel in the high bit of the low three.
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'a' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'b' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'c' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'd' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'e' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'f' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'g' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'h' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'i' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'j' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'k' => [0b101, 0b110, 0b100, 0b110, 0b101],
+        'l' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'm' => [0b111, 0b111, 0b101, 0b101, 0b101],
+        'n' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'o' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        'p' => [0b111, 0b101, 0b111, 0b100, 0b100],
+        'q' => [0b111, 0b101, 0b101, 0b111, 0b001],
+        'r' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        's' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        't' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'u' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'v' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'w' => [0b101, 0b101, 0b101, 0b111, 0b101],
+        'x' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '=' => [0b000, 0b111, 0b000, 0b111, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '[' => [0b110, 0b100, 0b100, 0b100, 0b110],
+        ']' => [0b011, 0b001, 0b001, 0b001, 0b011],
+        _ => [0; 5],
+    }
+}
+
+/// Appends a population sample to the sparkline ring buffer, dropping
+/// the oldest once the history cap is reached.
+fn push_population(history: &mut VecDeque<usize>, population: usize) {
+    if history.len() == SPARKLINE_HISTORY {
+        history.pop_front();
+    }
+    history.push_back(population);
+}
+
+/// Draws the recent population history as a bar graph along the bottom
+/// edge of the frame, newest sample at the right, scaled to the largest
+/// count currently in the window.
+fn draw_sparkline(frame: &mut [u8], frame_width: u32, frame_height: u32, history: &VecDeque<usize>) {
+    if history.is_empty() {
+        return;
+    }
+    let peak = history.iter().copied().max().unwrap_or(1).max(1);
+    let top = frame_height.saturating_sub(SPARKLINE_HEIGHT);
+    // Dim backing strip so the bars read against any board.
+    for y in top..frame_height {
+        for x in 0..frame_width {
+            let i = ((y * frame_width + x) * 4) as usize;
+            if let Some(pixel) = frame.get_mut(i..i + 4) {
+                pixel.copy_from_slice(&[0x10, 0x10, 0x10, 0xff]);
+            }
+        }
+    }
+    for (index, &population) in history.iter().rev().enumerate() {
+        if index as u32 >= frame_width {
+            break;
+        }
+        let x = frame_width - 1 - index as u32;
+        let bar = (population as u64 * (SPARKLINE_HEIGHT - 1) as u64 / peak as u64) as u32;
+        for y in (frame_height - 1 - bar)..frame_height {
+            let i = ((y * frame_width + x) * 4) as usize;
+            if let Some(pixel) = frame.get_mut(i..i + 4) {
+                pixel.copy_from_slice(&[0x5f, 0xd3, 0x5f, 0xff]);
+            }
+        }
+    }
+}
+
+/// Draws a downsampled map of the whole board in the top-right corner,
+/// with a rectangle marking the region the viewport currently shows.
+/// One nearest-cell sample per map pixel bounds the cost by
+/// [`MINIMAP_SIZE`] squared, however large the world is.
+fn draw_minimap(frame: &mut [u8], frame_width: u32, frame_height: u32, world: &World) {
+    let (world_width, world_height) = world.dimensions();
+    // Fit the world's aspect ratio inside the size cap.
+    let longest = world_width.max(world_height);
+    let map_width = (world_width * MINIMAP_SIZE / longest).max(1);
+    let map_height = (world_height * MINIMAP_SIZE / longest).max(1);
+    let origin_x = frame_width.saturating_sub(map_width + 2);
+    let origin_y = 2;
+    let put = |frame: &mut [u8], x: u32, y: u32, color: [u8; 4]| {
+        let i = ((y * frame_width + x) * 4) as usize;
+        if let Some(pixel) = frame.get_mut(i..i + 4) {
+            pixel.copy_from_slice(&color);
+        }
+    };
+
+    for my in 0..map_height {
+        for mx in 0..map_width {
+            let x = (mx as u64 * world_width as u64 / map_width as u64) as u32;
+            let y = (my as u64 * world_height as u64 / map_height as u64) as u32;
+            let color = if world.get(x, y) {
+                [0xe8, 0xe8, 0xe8, 0xff]
             } else {
-                [0x48, 0xb2, 0xe8, 0xff]
+                [0x10, 0x10, 0x10, 0xff]
             };
+            put(frame, origin_x + mx, origin_y + my, color);
+        }
+    }
+
+    // Outline the cells the viewport has on screen, clamped to the map.
+    let to_map = |cell: i64, world_side: u32, map_side: u32| {
+        let scaled = cell.clamp(0, world_side as i64) as u64 * map_side as u64 / world_side as u64;
+        (scaled as u32).min(map_side - 1)
+    };
+    let scale_x = world.viewport.scale_x.max(1) as i64;
+    let scale_y = world.viewport.scale_y.max(1) as i64;
+    let left = to_map(world.viewport.x, world_width, map_width);
+    let right = to_map(
+        world.viewport.x + (frame_width as i64) / scale_x,
+        world_width,
+        map_width,
+    );
+    let top = to_map(world.viewport.y, world_height, map_height);
+    let bottom = to_map(
+        world.viewport.y + (frame_height as i64) / scale_y,
+        world_height,
+        map_height,
+    );
+    let view = [0xff, 0xd7, 0x00, 0xff];
+    for mx in left..=right {
+        put(frame, origin_x + mx, origin_y + top, view);
+        put(frame, origin_x + mx, origin_y + bottom, view);
+    }
+    for my in top..=bottom {
+        put(frame, origin_x + left, origin_y + my, view);
+        put(frame, origin_x + right, origin_y + my, view);
+    }
+}
 
-            pixel.copy_from_slice(&rgba);
+/// Draws overlay text into an RGBA frame at the given pixel position,
+/// white on a black backing box for legibility on any board.
+fn draw_text(frame: &mut [u8], frame_width: u32, x: u32, y: u32, text: &str) {
+    for (index, c) in text.chars().enumerate() {
+        let origin_x = x + index as u32 * 4 * OVERLAY_SCALE;
+        for row in 0..5u32 {
+            for col in 0..4u32 {
+                let lit = col < 3 && glyph(c)[row as usize] & (0b100 >> col) != 0;
+                let color = if lit { 0xff } else { 0x00 };
+                for dy in 0..OVERLAY_SCALE {
+                    for dx in 0..OVERLAY_SCALE {
+                        let px = origin_x + col * OVERLAY_SCALE + dx;
+                        let py = y + row * OVERLAY_SCALE + dy;
+                        let i = ((py * frame_width + px) * 4) as usize;
+                        if let Some(pixel) = frame.get_mut(i..i + 4) {
+                            pixel.copy_from_slice(&[color, color, color, 0xff]);
+                        }
+                    }
+                }
+            }
         }
     }
 }
+
+/// Simulates N generations without a window and, with `--dump`, prints
+/// the final board as plaintext rows for scripting and diffing.
+#[cfg(not(target_arch = "wasm32"))]
+fn run_generations(args: &Args, generations: u64, rng: &mut fastrand::Rng) {
+    let mut world = initial_world(args, rng);
+    let mut stats = open_stats(args);
+    let mut renderer = open_frames(args);
+    record_stats(&mut stats, &world, false);
+    if let Some(renderer) = renderer.as_mut() {
+        renderer.render(&world);
+    }
+    for _ in 0..generations {
+        world.update();
+        world.apply_noise(args.noise, rng);
+        record_stats(&mut stats, &world, false);
+        if let Some(renderer) = renderer.as_mut() {
+            renderer.render(&world);
+        }
+    }
+    record_stats(&mut stats, &world, true);
+
+    if args.dump {
+        let mut out = String::new();
+        for y in 0..world.height() {
+            for x in 0..world.width() {
+                out.push(if world.get(x, y) { 'O' } else { '.' });
+            }
+            out.push('\n');
+        }
+        print!("{out}");
+    }
+}
+
+/// Maps a frame pixel position to the world cell under it, through the
+/// world's viewport. The result can fall outside the world.
+fn cursor_cell(world: &World, px: usize, py: usize) -> (i64, i64) {
+    (
+        world.viewport.x + (px / world.viewport.scale_x.max(1) as usize) as i64,
+        world.viewport.y + (py / world.viewport.scale_y.max(1) as usize) as i64,
+    )
+}
+
+/// Runs the simulation in the terminal, redrawing in place each tick
+/// until the board stabilizes or the process is interrupted.
+#[cfg(not(target_arch = "wasm32"))]
+fn run_terminal(args: &Args, rng: &mut fastrand::Rng) {
+    let mut world = initial_world(args, rng);
+    let mut renderer: Box<dyn Renderer> = Box::new(TerminalRenderer);
+    // Clear the screen once; each frame then repaints from the home
+    // position to avoid flicker.
+    print!("\x1b[2J");
+    loop {
+        renderer.render(&world);
+        if world.period().is_some() {
+            println!("stabilized at generation {}", world.generation());
+            return;
+        }
+        world.update();
+        world.apply_noise(args.noise, rng);
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+fn update_title(window: &winit::window::Window, world: &World, brush_radius: u32) {
+    let stable = match world.period() {
+        Some(1) => " (stable)".to_string(),
+        Some(period) => format!(" (period {period})"),
+        None => String::new(),
+    };
+    let brush = if brush_radius > 0 {
+        format!(" — brush {}", brush_radius * 2 + 1)
+    } else {
+        String::new()
+    };
+    window.set_title(&format!(
+        "Game of Life — gen {} — pop {} ({:+}){stable}{brush}",
+        world.generation(), world.population(), world.population_delta()
+    ));
+}
+
+fn log_error<E: std::error::Error + 'static>(method_name: &str, err: E) {
+    error!("{method_name}() failed: {err}");
+    for source in err.sources().skip(1) {
+        error!("  Caused by: {source}");
+    }
+}
+
+/// Seconds since the Unix epoch, for timestamped file names.
+#[cfg(not(target_arch = "wasm32"))]
+fn epoch_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
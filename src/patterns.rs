@@ -0,0 +1,46 @@
+//! Built-in patterns as lists of live-cell coordinates relative to the
+//! pattern's top-left corner.
+
+/// The standard glider, oriented to travel down-right.
+pub const GLIDER: &[(i32, i32)] = &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+
+/// The Gosper glider gun, which emits a glider towards the bottom-right
+/// every 30 generations.
+pub const GOSPER_GLIDER_GUN: &[(i32, i32)] = &[
+    (0, 4),
+    (0, 5),
+    (1, 4),
+    (1, 5),
+    (10, 4),
+    (10, 5),
+    (10, 6),
+    (11, 3),
+    (11, 7),
+    (12, 2),
+    (12, 8),
+    (13, 2),
+    (13, 8),
+    (14, 5),
+    (15, 3),
+    (15, 7),
+    (16, 4),
+    (16, 5),
+    (16, 6),
+    (17, 5),
+    (20, 2),
+    (20, 3),
+    (20, 4),
+    (21, 2),
+    (21, 3),
+    (21, 4),
+    (22, 1),
+    (22, 5),
+    (24, 0),
+    (24, 1),
+    (24, 5),
+    (24, 6),
+    (34, 2),
+    (34, 3),
+    (35, 2),
+    (35, 3),
+];
@@ -0,0 +1,145 @@
+//! A sparse simulation backend for huge, mostly empty universes.
+
+use crate::{Rule, Simulation};
+use std::collections::{HashMap, HashSet};
+
+/// A world storing only the coordinates of live cells, allowing patterns
+/// to roam effectively unbounded coordinates without allocating a grid.
+pub struct SparseWorld {
+    pub rule: Rule,
+    pub generation: u64,
+    live: HashSet<(i64, i64)>,
+    /// Scratch tally of per-cell neighbour counts, reused across
+    /// generations to avoid reallocating every step.
+    counts: HashMap<(i64, i64), u8>,
+}
+
+impl SparseWorld {
+    pub fn new() -> Self {
+        Self {
+            rule: Rule::CONWAY,
+            generation: 0,
+            live: HashSet::new(),
+            counts: HashMap::new(),
+        }
+    }
+
+    /// ORs a pattern of relative live-cell coordinates into the world at
+    /// the given origin.
+    pub fn stamp(&mut self, pattern: &[(i32, i32)], origin_x: i64, origin_y: i64) {
+        for &(dx, dy) in pattern {
+            self.live.insert((origin_x + dx as i64, origin_y + dy as i64));
+        }
+    }
+
+    /// Iterates over the coordinates of every live cell, in no particular
+    /// order.
+    pub fn live_cells(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.live.iter().copied()
+    }
+}
+
+impl Default for SparseWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulation for SparseWorld {
+    fn update(&mut self) {
+        // Tally each live cell's contribution to its eight neighbours;
+        // only cells near the live set can change state.
+        self.counts.clear();
+        for &(x, y) in &self.live {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx == 0 && dy == 0 {
+                        // Ensure isolated live cells still get an entry so
+                        // the survival check below sees them.
+                        self.counts.entry((x, y)).or_insert(0);
+                    } else {
+                        *self.counts.entry((x + dx, y + dy)).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let rule = self.rule;
+        let live = &self.live;
+        self.live = self
+            .counts
+            .iter()
+            .filter(|&(cell, &num_neighbours)| {
+                if live.contains(cell) {
+                    rule.survives(num_neighbours)
+                } else {
+                    rule.born(num_neighbours)
+                }
+            })
+            .map(|(&cell, _)| cell)
+            .collect();
+        self.generation += 1;
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn population(&self) -> usize {
+        self.live.len()
+    }
+
+    fn get(&self, x: i64, y: i64) -> bool {
+        self.live.contains(&(x, y))
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, alive: bool) {
+        if alive {
+            self.live.insert((x, y));
+        } else {
+            self.live.remove(&(x, y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns;
+
+    #[test]
+    fn blinker_oscillates() {
+        let mut world = SparseWorld::new();
+        world.stamp(&[(0, 0), (1, 0), (2, 0)], 10, 10);
+
+        world.update();
+        let mut live: Vec<(i64, i64)> = world.live_cells().collect();
+        live.sort_unstable();
+        assert_eq!(live, [(11, 9), (11, 10), (11, 11)]);
+
+        world.update();
+        let mut live: Vec<(i64, i64)> = world.live_cells().collect();
+        live.sort_unstable();
+        assert_eq!(live, [(10, 10), (11, 10), (12, 10)]);
+    }
+
+    #[test]
+    fn glider_roams_unbounded_coordinates() {
+        let mut world = SparseWorld::new();
+        world.stamp(patterns::GLIDER, -3, -3);
+
+        // A glider translates by (1, 1) every four generations.
+        for _ in 0..400 {
+            world.update();
+        }
+        assert_eq!(world.population(), 5);
+        let mut live: Vec<(i64, i64)> = world.live_cells().collect();
+        live.sort_unstable();
+        let mut expected: Vec<(i64, i64)> = patterns::GLIDER
+            .iter()
+            .map(|&(dx, dy)| (dx as i64 + 97, dy as i64 + 97))
+            .collect();
+        expected.sort_unstable();
+        assert_eq!(live, expected);
+    }
+}